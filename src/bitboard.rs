@@ -0,0 +1,203 @@
+use std::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Not};
+
+use crate::{Player, Square};
+
+
+// Set of squares, one bit per square with a1 as the lowest bit and h8 as the highest.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+pub struct Bitboard(pub u64);
+
+impl Bitboard {
+    pub const EMPTY: Bitboard = Bitboard(0);
+
+    pub fn from_square(square: Square) -> Bitboard {
+        Bitboard(1 << square.index())
+    }
+
+    pub fn contains(&self, square: Square) -> bool {
+        self.0 & (1 << square.index()) != 0
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    pub fn count(&self) -> u32 {
+        self.0.count_ones()
+    }
+
+    // Lowest square of the set.
+    pub fn first(&self) -> Option<Square> {
+        Square::from_index(self.0.trailing_zeros() as usize)
+    }
+
+    // Squares of the set, from a1 towards h8.
+    pub fn squares(&self) -> impl Iterator<Item = Square> {
+        let mut bits:u64 = self.0;
+        std::iter::from_fn(move || {
+            let square = Square::from_index(bits.trailing_zeros() as usize)?;
+            bits &= bits - 1;
+            Some(square)
+        })
+    }
+}
+
+impl BitAnd for Bitboard {
+    type Output = Bitboard;
+
+    fn bitand(self, other: Bitboard) -> Bitboard {
+        Bitboard(self.0 & other.0)
+    }
+}
+
+impl BitOr for Bitboard {
+    type Output = Bitboard;
+
+    fn bitor(self, other: Bitboard) -> Bitboard {
+        Bitboard(self.0 | other.0)
+    }
+}
+
+impl BitXor for Bitboard {
+    type Output = Bitboard;
+
+    fn bitxor(self, other: Bitboard) -> Bitboard {
+        Bitboard(self.0 ^ other.0)
+    }
+}
+
+impl Not for Bitboard {
+    type Output = Bitboard;
+
+    fn not(self) -> Bitboard {
+        Bitboard(!self.0)
+    }
+}
+
+impl BitAndAssign for Bitboard {
+    fn bitand_assign(&mut self, other: Bitboard) {
+        self.0 &= other.0;
+    }
+}
+
+impl BitOrAssign for Bitboard {
+    fn bitor_assign(&mut self, other: Bitboard) {
+        self.0 |= other.0;
+    }
+}
+
+impl BitXorAssign for Bitboard {
+    fn bitxor_assign(&mut self, other: Bitboard) {
+        self.0 ^= other.0;
+    }
+}
+
+// Steps as (file, rank) offsets.
+const KING_STEPS: [(i32, i32); 8] = [(-1, 1), (0, 1), (1, 1), (-1, 0), (1, 0), (-1, -1), (0, -1), (1, -1)];
+const KNIGHT_STEPS: [(i32, i32); 8] = [(-1, 2), (1, 2), (-2, 1), (2, 1), (-2, -1), (2, -1), (-1, -2), (1, -2)];
+const WHITE_PAWN_CAPTURES: [(i32, i32); 2] = [(-1, 1), (1, 1)];
+const BLACK_PAWN_CAPTURES: [(i32, i32); 2] = [(-1, -1), (1, -1)];
+
+// The first four directions go towards higher squares, the last four towards lower ones.
+const DIRECTIONS: [(i32, i32); 8] = [(0, 1), (1, 0), (1, 1), (-1, 1), (0, -1), (-1, 0), (-1, -1), (1, -1)];
+const ROOK_DIRECTIONS: [usize; 4] = [0, 1, 4, 5];
+const BISHOP_DIRECTIONS: [usize; 4] = [2, 3, 6, 7];
+
+const KING_ATTACKS: [u64; 64] = step_table(&KING_STEPS);
+const KNIGHT_ATTACKS: [u64; 64] = step_table(&KNIGHT_STEPS);
+const PAWN_ATTACKS: [[u64; 64]; 2] = [step_table(&WHITE_PAWN_CAPTURES), step_table(&BLACK_PAWN_CAPTURES)];
+const RAYS: [[u64; 64]; 8] = ray_table();
+
+// Squares reached from each square by a single step, for kings, knights and pawn captures.
+const fn step_table(steps: &[(i32, i32)]) -> [u64; 64] {
+    let mut table = [0; 64];
+    let mut square = 0;
+    while square < 64 {
+        let (file, rank) = ((square % 8) as i32, (square / 8) as i32);
+        let mut step = 0;
+        while step < steps.len() {
+            let (to_file, to_rank) = (file + steps[step].0, rank + steps[step].1);
+            if to_file >= 0 && to_file < 8 && to_rank >= 0 && to_rank < 8 {
+                table[square] |= 1 << (to_rank * 8 + to_file);
+            }
+            step += 1;
+        }
+        square += 1;
+    }
+    table
+}
+
+// Squares from each square to the edge of the board, in each direction.
+const fn ray_table() -> [[u64; 64]; 8] {
+    let mut table = [[0; 64]; 8];
+    let mut direction = 0;
+    while direction < 8 {
+        let (file_step, rank_step) = DIRECTIONS[direction];
+        let mut square = 0;
+        while square < 64 {
+            let (mut file, mut rank) = ((square % 8) as i32 + file_step, (square / 8) as i32 + rank_step);
+            while file >= 0 && file < 8 && rank >= 0 && rank < 8 {
+                table[direction][square] |= 1 << (rank * 8 + file);
+                file += file_step;
+                rank += rank_step;
+            }
+            square += 1;
+        }
+        direction += 1;
+    }
+    table
+}
+
+// Ray in one direction, cut after the first occupied square.
+fn ray_attacks(square: Square, direction: usize, occupied: Bitboard) -> Bitboard {
+    let ray:u64 = RAYS[direction][square.index()];
+    let blockers:u64 = ray & occupied.0;
+    if blockers == 0 {
+        return Bitboard(ray);
+    }
+    let nearest:usize = if direction < 4 {
+        blockers.trailing_zeros() as usize
+    } else {
+        63 - blockers.leading_zeros() as usize
+    };
+    Bitboard(ray ^ RAYS[direction][nearest])
+}
+
+pub(crate) fn king_attacks(square: Square) -> Bitboard {
+    Bitboard(KING_ATTACKS[square.index()])
+}
+
+pub(crate) fn knight_attacks(square: Square) -> Bitboard {
+    Bitboard(KNIGHT_ATTACKS[square.index()])
+}
+
+// Squares a pawn of `player` standing on `square` captures on.
+pub(crate) fn pawn_attacks(square: Square, player: Player) -> Bitboard {
+    let side:usize = match player {
+        Player::White => 0,
+        Player::Black => 1,
+    };
+    Bitboard(PAWN_ATTACKS[side][square.index()])
+}
+
+pub(crate) fn rook_attacks(square: Square, occupied: Bitboard) -> Bitboard {
+    ROOK_DIRECTIONS.iter().fold(Bitboard::EMPTY, |attacks, direction| attacks | ray_attacks(square, *direction, occupied))
+}
+
+pub(crate) fn bishop_attacks(square: Square, occupied: Bitboard) -> Bitboard {
+    BISHOP_DIRECTIONS.iter().fold(Bitboard::EMPTY, |attacks, direction| attacks | ray_attacks(square, *direction, occupied))
+}
+
+pub(crate) fn queen_attacks(square: Square, occupied: Bitboard) -> Bitboard {
+    rook_attacks(square, occupied) | bishop_attacks(square, occupied)
+}
+
+// Squares strictly between two squares on a line, empty when they don't line up.
+pub(crate) fn between(from: Square, to: Square) -> Bitboard {
+    for ray in RAYS.iter() {
+        if ray[from.index()] & (1 << to.index()) != 0 {
+            return Bitboard(ray[from.index()] & !ray[to.index()] & !(1 << to.index()));
+        }
+    }
+    Bitboard::EMPTY
+}
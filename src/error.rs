@@ -0,0 +1,43 @@
+use std::error::Error;
+use std::fmt;
+
+use crate::{GameOutcome, Piece, Player, Square};
+
+
+// Why an operation on the board failed.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ChessError {
+    NoPieceOnSquare(Square),
+    WrongSide { square: Square, side_to_move: Player },  // The piece belongs to the player who is waiting
+    IllegalMove { from: Square, to: Square },  // The piece doesn't move that way
+    BlockedPath { from: Square, to: Square },
+    LeavesKingInCheck { from: Square, to: Square },
+    InvalidPromotion(Piece),
+    InvalidSquare(String),
+    InvalidNotation(String),
+    NoMatchingMove(String),  // Well formed notation, but no legal move fits it
+    AmbiguousMove(String),  // Notation that fits more than one legal move
+    InvalidFen(String),
+    GameOver(GameOutcome),  // No more moves can be played
+}
+
+impl fmt::Display for ChessError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ChessError::NoPieceOnSquare(square) => write!(f, "No piece on {}", square),
+            ChessError::WrongSide { square, side_to_move } => write!(f, "The piece on {} can't move, it is {:?}'s turn", square, side_to_move),
+            ChessError::IllegalMove { from, to } => write!(f, "The piece on {} can't move to {}", from, to),
+            ChessError::BlockedPath { from, to } => write!(f, "The way from {} to {} is blocked", from, to),
+            ChessError::LeavesKingInCheck { from, to } => write!(f, "Moving from {} to {} leaves the king in check", from, to),
+            ChessError::InvalidPromotion(piece) => write!(f, "A pawn can't promote to {:?}", piece),
+            ChessError::InvalidSquare(name) => write!(f, "Invalid square '{}'", name),
+            ChessError::InvalidNotation(notation) => write!(f, "Invalid notation '{}'", notation),
+            ChessError::NoMatchingMove(notation) => write!(f, "No legal move matches '{}'", notation),
+            ChessError::AmbiguousMove(notation) => write!(f, "'{}' could be more than one move, give the file or rank it starts from", notation),
+            ChessError::InvalidFen(reason) => write!(f, "Invalid FEN: {}", reason),
+            ChessError::GameOver(outcome) => write!(f, "The game is over: {}", outcome),
+        }
+    }
+}
+
+impl Error for ChessError {}
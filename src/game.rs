@@ -0,0 +1,282 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::{ChessBoard, ChessError, GameOutcome, Move, Piece, Player, Position, Square};
+
+
+// A game session: the board, where it started from, who plays and how it ended.
+// Meant to be driven the same way by the terminal, the tests or any other frontend.
+#[derive(Clone)]
+pub struct Game {
+    start: Position,
+    board: ChessBoard,
+    tags: Vec<(String, String)>,  // PGN tags in the order they are written
+    outcome: Option<GameOutcome>,  // Endings the board can't see by itself: resignation, agreement, claims, time
+}
+
+impl Default for Game {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Game {
+    pub fn new() -> Self {
+        Game {
+            start: Position::new(),
+            board: ChessBoard::new(),
+            tags: seven_tag_roster(),
+            outcome: None,
+        }
+    }
+
+    // A game from a set up position, recorded as such in the PGN tags.
+    pub fn from_position(position: Position) -> Self {
+        let mut game = Game {
+            start: position,
+            board: ChessBoard::from_position(position),
+            tags: seven_tag_roster(),
+            outcome: None,
+        };
+        if position != Position::new() {
+            game.set_tag("SetUp", "1");
+            game.set_tag("FEN", &position.write_to_fen());
+        }
+        game
+    }
+
+    pub fn board(&self) -> &ChessBoard {
+        &self.board
+    }
+
+    pub fn start_position(&self) -> &Position {
+        &self.start
+    }
+
+    // Moves played from the starting position, oldest first.
+    pub fn moves(&self) -> Vec<Move> {
+        self.board.moves()
+    }
+
+    pub fn tag(&self, name: &str) -> Option<&str> {
+        self.tags.iter().find(|(tag, _)| tag == name).map(|(_, value)| value.as_str())
+    }
+
+    pub fn tags(&self) -> &[(String, String)] {
+        &self.tags
+    }
+
+    // Replaces the tag if it is already there, adds it at the end otherwise.
+    pub fn set_tag(&mut self, name: &str, value: &str) {
+        set_tag(&mut self.tags, name, value);
+    }
+
+    pub fn set_players(&mut self, white: &str, black: &str) {
+        self.set_tag("White", white);
+        self.set_tag("Black", black);
+    }
+
+    pub fn player_name(&self, player: Player) -> &str {
+        let tag:&str = match player {
+            Player::White => "White",
+            Player::Black => "Black",
+        };
+        self.tag(tag).unwrap_or("?")
+    }
+
+    // Plays a legal move of the side to move.
+    pub fn play(&mut self, mv: Move) -> Result<(), ChessError> {
+        self.check_not_over()?;
+        let legal:Move = self.board.position().validate_move(mv.from, mv.to, mv.promotion)?;
+        self.play_legal(legal)
+    }
+
+    // Plays the move between two squares, if it is legal.
+    pub fn play_squares(&mut self, from: Square, to: Square, promotion: Option<Piece>) -> Result<(), ChessError> {
+        let mv:Move = self.board.position().build_move(from, to, promotion).ok_or(ChessError::NoPieceOnSquare(from))?;
+        // The promotion is checked as given, even one a move can't have.
+        self.play(Move { promotion, ..mv })
+    }
+
+    // Plays a move written in SAN, such as "Nf3" or "exd5".
+    pub fn play_san(&mut self, san: &str) -> Result<(), ChessError> {
+        self.check_not_over()?;
+        let mv:Move = self.board.position().parse_san(san)?;
+        self.play_legal(mv)
+    }
+
+    // Plays a move written in UCI, such as "e2e4" or "e7e8q".
+    pub fn play_uci(&mut self, uci: &str) -> Result<(), ChessError> {
+        self.check_not_over()?;
+        let mv:Move = self.board.position().parse_uci(uci)?;
+        self.play_legal(mv)
+    }
+
+    // The move has been checked against the rules and the game is still on.
+    fn play_legal(&mut self, mv: Move) -> Result<(), ChessError> {
+        self.board.move_piece(mv.from, mv.to, mv.promotion)
+    }
+
+    fn check_not_over(&self) -> Result<(), ChessError> {
+        match self.outcome() {
+            Some(outcome) => Err(ChessError::GameOver(outcome)),
+            None => Ok(()),
+        }
+    }
+
+    // Takes back the last move. A game that was over goes on again.
+    pub fn undo(&mut self) -> Option<Move> {
+        let mv:Move = self.board.undo()?;
+        self.outcome = None;
+        Some(mv)
+    }
+
+    pub fn redo(&mut self) -> Option<Move> {
+        if self.outcome().is_some() {
+            return None;
+        }
+        self.board.redo()
+    }
+
+    pub fn resign(&mut self, player: Player) {
+        self.end(GameOutcome::Resignation { winner: player.opponent() });
+    }
+
+    pub fn agree_draw(&mut self) {
+        self.end(GameOutcome::Agreement);
+    }
+
+    pub fn lose_on_time(&mut self, player: Player) {
+        self.end(GameOutcome::Timeout { winner: player.opponent() });
+    }
+
+    // Ends the game in a draw if the position allows one to be claimed.
+    pub fn claim_draw(&mut self) -> Option<GameOutcome> {
+        let outcome:GameOutcome = self.board.claim_draw()?;
+        self.end(outcome);
+        Some(outcome)
+    }
+
+    // A game already decided keeps its first outcome.
+    fn end(&mut self, outcome: GameOutcome) {
+        if self.outcome().is_none() {
+            self.outcome = Some(outcome);
+        }
+    }
+
+    pub fn outcome(&self) -> Option<GameOutcome> {
+        self.outcome.or_else(|| self.board.outcome())
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.outcome().is_some()
+    }
+
+    // Result in PGN format, "*" while the game goes on.
+    pub fn result(&self) -> &'static str {
+        self.outcome().map_or("*", |outcome| outcome.result())
+    }
+
+    pub fn to_pgn(&self) -> String {
+        let mut tags:Vec<(String, String)> = self.tags.clone();
+        set_tag(&mut tags, "Result", self.result());
+        write_pgn(&tags, &self.board.write_movetext(), self.result())
+    }
+}
+
+
+// Tags every PGN game starts with, in the order the standard gives them.
+pub(crate) fn seven_tag_roster() -> Vec<(String, String)> {
+    let version:&str = option_env!("CARGO_PKG_VERSION").unwrap_or("");
+    [
+        ("Event", "Chess Game".to_string()),
+        ("Site", format!("chessme {}", version)),
+        ("Date", today()),
+        ("Round", "1".to_string()),
+        ("White", "?".to_string()),
+        ("Black", "?".to_string()),
+        ("Result", "*".to_string()),
+    ].into_iter().map(|(name, value)| (name.to_string(), value)).collect()
+}
+
+
+pub(crate) fn set_tag(tags: &mut Vec<(String, String)>, name: &str, value: &str) {
+    match tags.iter_mut().find(|(tag, _)| tag == name) {
+        Some((_, old_value)) => *old_value = value.to_string(),
+        None => tags.push((name.to_string(), value.to_string())),
+    }
+}
+
+
+pub(crate) fn write_pgn(tags: &[(String, String)], movetext: &str, result: &str) -> String {
+    let mut pgn = String::new();
+    for (name, value) in tags {
+        // Quotes and backslashes inside a value are escaped with a backslash.
+        let value:String = value.replace('\\', "\\\\").replace('"', "\\\"");
+        pgn.push_str(&format!("[{} \"{}\"]\n", name, value));
+    }
+    pgn.push('\n');
+    pgn.push_str(movetext);
+    pgn.push_str(result);
+    pgn
+}
+
+
+fn is_leap_year(year: i32) -> bool {
+    (year % 4 == 0 && year % 100 != 0) || (year % 400 == 0)
+}
+
+
+fn days_in_month(year: i32, month: i32) -> i32 {
+    match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        2 => if is_leap_year(year) { 29 } else { 28 },
+        _ => 0,
+    }
+}
+
+
+fn today() -> String {
+    let now = SystemTime::now();
+    let duration_since_epoch = now.duration_since(UNIX_EPOCH).unwrap();
+    let seconds = duration_since_epoch.as_secs();
+    
+    // Calculate days since the epoch (1970-01-01)
+    let days_since_epoch = seconds / 86400; // 86400 seconds per day
+    
+    // Calculate the year
+    let mut year = 1970;
+    let mut days_in_year = 365;
+    
+    // Loop through years to find the correct year
+    let mut remaining_days = days_since_epoch as i32;
+    while remaining_days >= days_in_year {
+        if is_leap_year(year) {
+            days_in_year = 366;
+        } else {
+            days_in_year = 365;
+        }
+        
+        if remaining_days < days_in_year {
+            break;
+        }
+        
+        remaining_days -= days_in_year;
+        year += 1;
+    }
+
+    // Now find the month and day
+    let mut month = 1;
+    let mut days_in_current_month = days_in_month(year, month);
+    
+    while remaining_days >= days_in_current_month {
+        remaining_days -= days_in_current_month;
+        month += 1;
+        days_in_current_month = days_in_month(year, month);
+    }
+
+    let day = remaining_days + 1; // The remaining days are the current day of the month
+    
+    // Output the result, the way PGN writes dates
+    format!("{year:04}.{month:02}.{day:02}")
+}
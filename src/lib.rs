@@ -1,5 +1,21 @@
-use std::time::{SystemTime, UNIX_EPOCH};
 use std::collections::VecDeque;
+use std::fmt;
+
+mod bitboard;
+mod error;
+mod game;
+mod position;
+mod san;
+mod square;
+mod uci;
+mod zobrist;
+
+pub use bitboard::Bitboard;
+pub use error::ChessError;
+pub use game::Game;
+pub use position::{CastlingRights, EnPassantMode, Pin, Position, UndoInfo};
+pub use square::{File, Rank, Square};
+pub use uci::CastlingMode;
 
 
 #[derive(Copy, Clone, Debug, PartialEq)]
@@ -17,535 +33,303 @@ pub enum Piece {
     Knight(Player),
     Pawn(Player),
 }
-#[derive(Clone)]
-pub struct ChessBoard {
-    pub board: [[Option<Piece>; 8]; 8],
-    pub moves_history: VecDeque<String>  // Track the moves in PGN format
-}
 
-impl ChessBoard {
-    pub fn new() -> Self {
-        let moves_history:VecDeque<String> = VecDeque::new();
-        let mut board:[[Option<Piece>; 8]; 8] = [[None; 8]; 8];
-
-        // Place white pieces
-        board[0][0] = Some(Piece::Rook(Player::White));
-        board[0][1] = Some(Piece::Knight(Player::White));
-        board[0][2] = Some(Piece::Bishop(Player::White));
-        board[0][3] = Some(Piece::Queen(Player::White));
-        board[0][4] = Some(Piece::King(Player::White));
-        board[0][5] = Some(Piece::Bishop(Player::White));
-        board[0][6] = Some(Piece::Knight(Player::White));
-        board[0][7] = Some(Piece::Rook(Player::White));
-        for i in 0..8 {
-            board[1][i] = Some(Piece::Pawn(Player::White));
+impl Piece {
+    pub fn player(&self) -> Player {
+        match *self {
+            Piece::King(p) | Piece::Queen(p) | Piece::Rook(p) | Piece::Bishop(p) | Piece::Knight(p) | Piece::Pawn(p) => p,
         }
+    }
+}
 
-        // Place black pieces
-        board[7][0] = Some(Piece::Rook(Player::Black));
-        board[7][1] = Some(Piece::Knight(Player::Black));
-        board[7][2] = Some(Piece::Bishop(Player::Black));
-        board[7][3] = Some(Piece::Queen(Player::Black));
-        board[7][4] = Some(Piece::King(Player::Black));
-        board[7][5] = Some(Piece::Bishop(Player::Black));
-        board[7][6] = Some(Piece::Knight(Player::Black));
-        board[7][7] = Some(Piece::Rook(Player::Black));
-        for i in 0..8 {
-            board[6][i] = Some(Piece::Pawn(Player::Black));
+impl Player {
+    pub fn opponent(&self) -> Player {
+        match *self {
+            Player::White => Player::Black,
+            Player::Black => Player::White,
         }
-
-        ChessBoard { board , moves_history}
     }
+}
 
-    pub fn print(&self) {
-        print!("{}[2J", 27 as char);
-        for row in self.board.iter().rev() {
-            for cell in row.iter() {
-                match cell {
-                    Some(piece) => {
-                        let symbol = match piece {
-                            Piece::King(player) => if *player == Player::White { "♔" } else { "♚" },
-                            Piece::Queen(player) => if *player == Player::White { "♕" } else { "♛" },
-                            Piece::Rook(player) => if *player == Player::White { "♖" } else { "♜" },
-                            Piece::Bishop(player) => if *player == Player::White { "♗" } else { "♝" },
-                            Piece::Knight(player) => if *player == Player::White { "♘" } else { "♞" },
-                            Piece::Pawn(player) => if *player == Player::White { "♙" } else { "♟" },
-                        };
-                        print!("{} ", symbol);
-                    },
-                    None => print!(". "),
-                }
-            }
-            println!();
+// How a game ended.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum GameOutcome {
+    Checkmate { winner: Player },
+    Stalemate,
+    ThreefoldRepetition,
+    FivefoldRepetition,
+    FiftyMoves,
+    SeventyFiveMoves,
+    InsufficientMaterial,
+    Resignation { winner: Player },
+    Timeout { winner: Player },
+    Agreement,
+}
+
+impl GameOutcome {
+    pub fn winner(&self) -> Option<Player> {
+        match *self {
+            GameOutcome::Checkmate { winner } | GameOutcome::Resignation { winner } | GameOutcome::Timeout { winner } => Some(winner),
+            _ => None,
         }
     }
 
-    pub fn move_piece(&mut self, start: (usize, usize), end: (usize, usize)) -> Result<(), String> {
-        let start_piece = self.board[start.0][start.1].ok_or("No piece at start position")?;
-        self.board[end.0][end.1] = Some(start_piece);
-        self.board[start.0][start.1] = None;
-        Ok(())
+    // Result in PGN format.
+    pub fn result(&self) -> &'static str {
+        match self.winner() {
+            Some(Player::White) => "1-0",
+            Some(Player::Black) => "0-1",
+            None => "1/2-1/2",
+        }
     }
+}
 
-    pub fn move_if_valid(&mut self, start: (usize, usize), end: (usize, usize), current_player: Player) -> bool {
-        let piece:Piece = match self.board[start.0][start.1] {
-            Some(piece) => piece,
-            None => return false,
-        };
-        
-        let is_valid:bool = self.clone().is_valid_move(start, end, current_player);
-
-        if is_valid {
-            let fen = self.write_to_fen(current_player);
-            println!(">> {fen}");
+impl fmt::Display for GameOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GameOutcome::Checkmate { winner } => write!(f, "{:?} wins by checkmate", winner),
+            GameOutcome::Stalemate => write!(f, "Draw by stalemate"),
+            GameOutcome::ThreefoldRepetition => write!(f, "Draw by threefold repetition"),
+            GameOutcome::FivefoldRepetition => write!(f, "Draw by fivefold repetition"),
+            GameOutcome::FiftyMoves => write!(f, "Draw by the fifty-move rule"),
+            GameOutcome::SeventyFiveMoves => write!(f, "Draw by the seventy-five-move rule"),
+            GameOutcome::InsufficientMaterial => write!(f, "Draw by insufficient material"),
+            GameOutcome::Resignation { winner } => write!(f, "{:?} wins by resignation", winner),
+            GameOutcome::Timeout { winner } => write!(f, "{:?} wins on time", winner),
+            GameOutcome::Agreement => write!(f, "Draw by agreement"),
+        }
+    }
+}
 
-            self.add_move(piece, end, current_player);
+// What makes a move special beyond going from one square to another.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum MoveKind {
+    Normal,
+    DoublePush,  // Pawn moving two squares from its initial row
+    EnPassant,
+    Castle,  // King moving two squares, the rook jumping over it
+}
 
-        };
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Move {
+    pub from: Square,
+    pub to: Square,
+    pub piece: Piece,  // Piece being moved
+    pub captured: Option<Piece>,  // Piece taken, not on `to` for en passant
+    pub promotion: Option<Piece>,  // Piece a pawn turns into on the last rank
+    pub kind: MoveKind,
+}
 
-        is_valid
+impl Move {
+    pub fn is_capture(&self) -> bool {
+        self.captured.is_some()
     }
 
-    pub fn next_player(&self, current_player: Player) -> Player {
-        let next_player:Player = match current_player {
-            Player::White => Player::Black,
-            Player::Black => Player::White,
-        };
-
-        next_player
+    pub fn is_castle(&self) -> bool {
+        self.kind == MoveKind::Castle
     }
 
-    pub fn is_valid_move(self, start: (usize, usize), end: (usize, usize), current_player: Player) -> bool {
-        let piece = match self.board[start.0][start.1] {
-            Some(piece) => piece,
-            None => return false,
-        };
-
-        // Make sure the piece belongs to the current player
-        if self.is_opponent(piece, current_player) {
-            return false;
-        }
-
-        if start.0 == end.0 && start.1 == end.1 {
-            return false;
-        }
-
-        if let Some(piece_at_the_end) = self.board[end.0][end.1] {
-            if !self.is_opponent(piece_at_the_end, current_player) {
-                // There is already a piece at the end position which belongs to current_player.
-                return false;
-            }
-        };
-
-        let is_valid: bool = match piece {
-            Piece::King(_) => self.is_valid_king_move(start, end),
-            Piece::Queen(_) => self.is_valid_queen_move(start, end),
-            Piece::Rook(_) => self.is_valid_rook_move(start, end),
-            Piece::Bishop(_) => self.is_valid_bishop_move(start, end),
-            Piece::Knight(_) => self.is_valid_knight_move(start, end),
-            Piece::Pawn(player) => self.is_valid_pawn_move(start, end, player),
-        };
-
-        is_valid
+    pub fn is_en_passant(&self) -> bool {
+        self.kind == MoveKind::EnPassant
     }
 
-    pub fn is_valid_king_move(&self, start: (usize, usize), end: (usize, usize)) -> bool {
-        let dx = (end.0 as isize - start.0 as isize).abs();
-        let dy = (end.1 as isize - start.1 as isize).abs();
-        dx <= 1 && dy <= 1
+    pub fn is_double_push(&self) -> bool {
+        self.kind == MoveKind::DoublePush
     }
+}
 
-    pub fn is_valid_queen_move(&self, start: (usize, usize), end: (usize, usize)) -> bool {
-        self.is_valid_rook_move(start, end) || self.is_valid_bishop_move(start, end)
-    }
+// A game in progress: the current position plus everything needed to write it down and take moves back.
+#[derive(Clone)]
+pub struct ChessBoard {
+    position: Position,
+    moves_history: VecDeque<String>,  // Moves played in SAN, as written in PGN
+    position_history: Vec<u64>,  // Repetition key of every position reached
+    played_moves: Vec<(Move, UndoInfo)>,  // Moves played so far, to take them back
+    undone_moves: Vec<Move>,  // Moves taken back, most recent last, to play them again
+}
 
-    pub fn is_valid_rook_move(&self, start: (usize, usize), end: (usize, usize)) -> bool {
-        if start.0 != end.0 && start.1 != end.1 {
-            return false;
-        }
+impl Default for ChessBoard {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
-        let (r1, c1) = start;
-        let (r2, c2) = end;
+impl ChessBoard {
+    pub fn new() -> Self {
+        Self::from_position(Position::new())
+    }
 
-        // Check if there are pieces in the way
-        if r1 == r2 {
-            let range = if c1 < c2 { c1 + 1..c2 } else { c2 + 1..c1 };
-            for col in range {
-                if self.board[r1][col].is_some() {
-                    return false;
-                }
-            }
-        } else {
-            let range = if r1 < r2 { r1 + 1..r2 } else { r2 + 1..r1 };
-            for row in range {
-                if self.board[row][c1].is_some() {
-                    return false;
-                }
-            }
+    // Starts a game from any position, e.g. a composed problem or an endgame.
+    pub fn from_position(position: Position) -> Self {
+        ChessBoard {
+            position,
+            moves_history: VecDeque::new(),
+            position_history: vec![position.repetition_key()],
+            played_moves: Vec::new(),
+            undone_moves: Vec::new(),
         }
+    }
 
-        true
+    pub fn from_fen(fen: &str) -> Result<Self, ChessError> {
+        Ok(Self::from_position(Position::from_fen(fen)?))
     }
 
-    pub fn is_valid_bishop_move(&self, start: (usize, usize), end: (usize, usize)) -> bool {
-        let dx = (end.0 as isize - start.0 as isize).abs();
-        let dy = (end.1 as isize - start.1 as isize).abs();
+    pub fn position(&self) -> &Position {
+        &self.position
+    }
 
-        if dx != dy || dx == 0 || dy == 0 {
-            return false;
-        }
+    pub fn moves_history(&self) -> &VecDeque<String> {
+        &self.moves_history
+    }
 
-        let (r1, c1) = start;
-        let (r2, c2) = end;
+    pub fn position_history(&self) -> &[u64] {
+        &self.position_history
+    }
 
-        let row_step = if r2 > r1 { 1 } else { -1 };
-        let col_step = if c2 > c1 { 1 } else { -1 };
+    // Moves played so far, oldest first.
+    pub fn moves(&self) -> Vec<Move> {
+        self.played_moves.iter().map(|(mv, _)| *mv).collect()
+    }
 
-        let mut row = r1 as isize + row_step;
-        let mut col = c1 as isize + col_step;
+    // Plays a move without checking the rules, e.g. to set up a position.
+    pub fn move_piece(&mut self, start: Square, end: Square, promotion: Option<Piece>) -> Result<(), ChessError> {
+        let mv:Move = self.position.build_move(start, end, promotion).ok_or(ChessError::NoPieceOnSquare(start))?;
 
-        let mut row_usize = row as usize;
-        let mut col_usize =col as usize;
-        while row != r2 as isize && col != c2 as isize && 0 < row_usize && row_usize < 8 && 0 < col_usize && col_usize < 8 {
-            if self.board[row_usize][col_usize].is_some() {
-                return false;
-            }
-            row += row_step;
-            col += col_step;
+        self.play_move(mv);
+        self.undone_moves.clear();
+        Ok(())
+    }
 
-            row_usize = row as usize;
-            col_usize = col as usize;
-        }
+    // Plays the move and records it in every history.
+    fn play_move(&mut self, mv: Move) {
+        // SAN is written from the position before the move.
+        self.moves_history.push_back(self.position.to_san(mv));
 
-        true
+        let undo:UndoInfo = self.position.make_move(mv);
+        self.played_moves.push((mv, undo));
+        self.position_history.push(self.position.repetition_key());
     }
 
-    pub fn is_valid_knight_move(&self, start: (usize, usize), end: (usize, usize)) -> bool {
-        let dx = (end.0 as isize - start.0 as isize).abs();
-        let dy = (end.1 as isize - start.1 as isize).abs();
-        (dx == 2 && dy == 1) || (dx == 1 && dy == 2)
+    // Takes back the last move played, returning it.
+    pub fn undo(&mut self) -> Option<Move> {
+        let (mv, undo) = self.played_moves.pop()?;
+        self.position.unmake_move(mv, undo);
+        self.position_history.pop();
+        self.moves_history.pop_back();
+        self.undone_moves.push(mv);
+        Some(mv)
     }
 
-    pub fn is_valid_pawn_move(&self, start: (usize, usize), end: (usize, usize), player: Player) -> bool {
-        let direction:isize = match player {
-            Player::White => 1,
-            Player::Black => -1,
-        };
-
-        let (r1, c1) = start;
-        let (r2, c2) = end;
-        
-        if c1 == c2 {
-            // Pawn moves straight.
-            
-            if self.board[r2][c2].is_some() {
-                // Can't move if there is a piece at the end.
-                return false
-            }
-            
-            // Pawn moves 1 square towards opponent.
-            if r2 == r1.wrapping_add_signed(direction) {
-                return true;
-            }
+    // Plays again the last move taken back, returning it.
+    pub fn redo(&mut self) -> Option<Move> {
+        let mv = self.undone_moves.pop()?;
+        self.play_move(mv);
+        Some(mv)
+    }
 
-            // Pawn can move two squares from its initial position
-            if r2 == r1.wrapping_add_signed(2 * direction) && ((r1 == 1 && player == Player::White )|| (r1 == 6 && player == Player::Black)) {
-                return self.board[r1.wrapping_add_signed(direction)][c1].is_none();
-            }
-            return false;
-        }
+    // Whether the move may be played, without playing it.
+    pub fn check_move(&self, start: Square, end: Square, promotion: Option<Piece>) -> Result<(), ChessError> {
+        self.position.validate_move(start, end, promotion).map(|_| ())
+    }
 
-        // Pawn captures diagonally
-        if (r2 == r1.wrapping_add_signed(direction)) && c1 > 0 && (c2 == c1 + 1 || c2 == c1 - 1) {
-            if let Some(piece) = self.board[r2][c2] {
-                return self.is_opponent(piece, player);
-            }
-        }
+    pub fn write_to_pgn(&self, white_player: &str, black_player: &str, outcome: Option<GameOutcome>) -> String {
+        let result:&str = outcome.map_or("*", |outcome| outcome.result());
 
-        false
+        let mut tags = game::seven_tag_roster();
+        game::set_tag(&mut tags, "White", white_player);
+        game::set_tag(&mut tags, "Black", black_player);
+        game::set_tag(&mut tags, "Result", result);
+        game::write_pgn(&tags, &self.write_movetext(), result)
     }
 
-    pub fn is_opponent(&self, piece:Piece,  player: Player) -> bool {
-        match piece {
-            Piece::King(p) => p != player,
-            Piece::Queen(p) => p != player,
-            Piece::Rook(p) => p != player,
-            Piece::Bishop(p) => p != player,
-            Piece::Knight(p) => p != player,
-            Piece::Pawn(p) => p != player,
+    // Moves in PGN format, numbered, without the result.
+    pub(crate) fn write_movetext(&self) -> String {
+        let mut movetext = String::new();
+        for ((mv, undo), san) in self.played_moves.iter().zip(self.moves_history.iter()) {
+            // White moves carry the move number, so does a first move by Black, as "12... Nf6".
+            match mv.piece.player() {
+                Player::White => movetext.push_str(&format!("{}. ", undo.fullmove_number)),
+                Player::Black if movetext.is_empty() => movetext.push_str(&format!("{}... ", undo.fullmove_number)),
+                Player::Black => {},
+            }
+            movetext.push_str(san);
+            movetext.push(' ');
         }
+        movetext
     }
 
-    pub fn write_to_fen(&self, current_player: Player) -> String {
-        let mut fen = String::new();
-
-        // Piece Placement
-        for row in self.board.iter().rev() {
-            let mut empty_count = 0;
-            for cell in row.iter() {
-                match cell {
-                    Some(piece) => {
-                        if empty_count > 0 {
-                            fen.push_str(&empty_count.to_string());
-                            empty_count = 0;
-                        }
-                        let symbol = match piece {
-                            Piece::King(player) => if *player == Player::White { "K" } else { "k" },
-                            Piece::Queen(player) => if *player == Player::White { "Q" } else { "q" },
-                            Piece::Rook(player) => if *player == Player::White { "R" } else { "r" },
-                            Piece::Bishop(player) => if *player == Player::White { "B" } else { "b" },
-                            Piece::Knight(player) => if *player == Player::White { "N" } else { "n" },
-                            Piece::Pawn(player) => if *player == Player::White { "P" } else { "p" },
-                        };
-                        fen.push_str(symbol);
-                    },
-                    None => {
-                        empty_count += 1;
-                    },
-                }
+    pub fn outcome(&self) -> Option<GameOutcome> {
+        // A side to move with no legal moves is either checkmated or stalemated.
+        if !self.position.has_legal_moves() {
+            if self.position.is_check() {
+                return Some(GameOutcome::Checkmate { winner: self.position.side_to_move().opponent() });
             }
-            if empty_count > 0 {
-                fen.push_str(&empty_count.to_string());
-            }
-            fen.push('/');
+            return Some(GameOutcome::Stalemate);
         }
-        fen.pop(); // Remove the last '/'.
-
-        // Active color
-        fen.push(' ');
-        fen.push(if current_player == Player::White { 'w' } else { 'b' });
-
-        // TODO: Add Castling, En Passant, Halfmove Clock, and Fullmove Number
 
-        fen
-    }
-
-    pub fn write_to_pgn(&self, white_player: &str, black_player: &str, result: &str) -> String {
-        // Construct PGN header
-        let default_version = "";
-        let version = option_env!("CARGO_PKG_VERSION").ok_or(default_version).unwrap();
-        let today = today();
-        let mut pgn = format!(
-            "[Event \"Chess Game\"]\n[Site \"chessme {}\"]\n[Date \"{}\"]\n[Round \"1\"]\n[White \"{}\"]\n[Black \"{}\"]\n[Result \"{}\"]\n\n",
-            version, today, white_player, black_player, result
-        );
-
-        // Add the moves
-        let mut move_counter = 1;
-        for chunk in self.moves_history.iter() {
-            pgn.push_str(&format!("{}. {} ", move_counter, chunk));
-            move_counter += 1;
+        // Nobody can ever be checkmated anymore.
+        if self.position.is_insufficient_material() {
+            return Some(GameOutcome::InsufficientMaterial);
         }
 
-        pgn
-    }
-
-    pub fn add_move(&mut self, piece:Piece,end: (usize, usize), current_player: Player) {
-        let letter = match piece {
-            Piece::King(current_player) => if current_player == Player::White { "K" } else { "k" },
-            Piece::Queen(current_player) => if current_player == Player::White { "Q" } else { "q" },
-            Piece::Rook(current_player) => if current_player == Player::White { "R" } else { "r" },
-            Piece::Bishop(current_player) => if current_player == Player::White { "B" } else { "b" },
-            Piece::Knight(current_player) => if current_player == Player::White { "N" } else { "n" },
-            Piece::Pawn(current_player) => if current_player == Player::White { "" } else { "" },
-        };
-        let ending = match current_player {
-            Player::White => " ",
-            Player::Black => "\n"
-        };
-        let column = (b'a' + end.0 as u8) as char;  // Convert the column index to a letter
-        let row = end.1.to_string();  // Convert the row index to a string
-
-        let move_notation : String= format!("{letter}{column}{row}{ending}");
-        
-        self.moves_history.push_back(move_notation);
-    }
-
-    pub fn result(&mut self, current_player: Player) -> String {
-        let opponent:Player = self.next_player(current_player);
-
-        let current_player_cant_move:bool = self.has_legal_moves(current_player);
-        let opponent_cant_move:bool = self.has_legal_moves(opponent);
-
-        if self.is_check(current_player) && !current_player_cant_move {
-            if current_player == Player::White {
-                return "0-1".to_string();
-            } else {
-                return "1-0".to_string();
-            }
+        // Seventy-five moves by each side without a capture or pawn move end the game on their own.
+        if self.position.halfmove_clock() >= 150 {
+            return Some(GameOutcome::SeventyFiveMoves);
         }
-        if self.is_check(opponent) && !opponent_cant_move {
-            if opponent == Player::White {
-                return "0-1".to_string();
-            } else {
-                return "1-0".to_string();
-            }
 
-        }
-        // Check if it's a stalemate (no legal moves, and not in check)
-        if !current_player_cant_move {
-            return "1/2-1/2".to_string();
+        // So does the same position coming back for the fifth time.
+        if self.repetition_count() >= 5 {
+            return Some(GameOutcome::FivefoldRepetition);
         }
 
         // If no conclusion yet, game is still ongoing
-        "*".to_string()
+        None
     }
 
-    pub fn is_check(&mut self, player: Player) -> bool {
-        // Find the player's king position
-        let king_position: (usize, usize) = match self.find_king_position(player){
-            Some(position)=> position,
-            None => return false
-        };
-        let opponent: Player = self.next_player(player);
-
-        // Check if any opposing piece can attack the king
-        for row in 0..8 {
-            for col in 0..8 {
-                if self.board[row][col].is_some() {
-                    if self.clone().is_valid_move( (row, col), king_position, opponent) {
-                        return true
-                    }
-                }
-            }
+    // Draws a player may ask for but which don't end the game by themselves.
+    pub fn claim_draw(&self) -> Option<GameOutcome> {
+        if self.position.can_claim_fifty_moves() {
+            return Some(GameOutcome::FiftyMoves);
         }
-        
-        return false
-    }
-
-    pub fn find_king_position(&self, player: Player) -> Option<(usize, usize)> {
-        for row in 0..8 {
-            for col in 0..8 {
-                if let Some(piece) = self.board[row][col] {
-                    if let Piece::King(piece_player) = piece {
-                        if piece_player == player {
-                            return Some((row, col));
-                        }
-                    }
-                }
-            }
+        if self.can_claim_threefold_repetition() {
+            return Some(GameOutcome::ThreefoldRepetition);
         }
-        // panic!("King not found on the board!");
         None
     }
 
-    pub fn has_legal_moves(&mut self, player: Player) -> bool {
-        let is_currently_in_check:bool = self.is_check(player);
-        let opponent:Player = self.next_player(player);
-        
-        for row in 0..8 {
-            for col in 0..8 {
-                let this_piece: Piece = match self.board[row][col] {
-                    Some(piece) => piece,
-                    None => continue
-                };
-                if self.is_opponent(this_piece, player) {
-                    continue
-                }
-
-                for target_row in 0..8 {
-                    for target_col in 0..8 {               
-                        if row == target_row && col == target_col {
-                            continue
-                        }
-                        if self.clone().is_valid_move((row, col), (target_row, target_col), player) {
-                            if !is_currently_in_check {
-                                return true
-                            }   
-                            if  self.board[target_row][target_col].is_some_and(|p| p == Piece::King(opponent)) {
-                                continue;
-                            }
-                            
-                            // Will this move will "uncheck" the king ?
-                            let mut possible_board:ChessBoard = self.clone();
-                            let moved:bool = possible_board.move_piece((row, col), (target_row, target_col)).is_ok();
-                            if moved && is_currently_in_check && !possible_board.is_check(player) { 
-                                // King is now unchecked.
-                                return true
-                            }
-                        }
-                    }
-                }
-            }
-        }
-        false
+    pub fn can_claim_threefold_repetition(&self) -> bool {
+        self.repetition_count() >= 3
     }
-}
 
-
-pub fn parse_position(position: &str) -> Option<(usize, usize)> {
-    let chars: Vec<char> = position.chars().collect();
-    if chars.len() != 2 {
-        return None;
+    // How many times the current position has been reached, itself included.
+    pub fn repetition_count(&self) -> usize {
+        match self.position_history.last() {
+            Some(key) => self.position_history.iter().filter(|k| *k == key).count(),
+            None => 0,
+        }
     }
 
-    let column = chars[0] as usize - 'a' as usize;
-    let row = chars[1] as usize - '1' as usize;
-
-    if column < 8 && row < 8 {
-        Some((row, column))
-    } else {
-        None
+    pub fn result(&self) -> String {
+        match self.outcome() {
+            Some(outcome) => outcome.result().to_string(),
+            None => "*".to_string(),
+        }
     }
 }
 
 
-fn is_leap_year(year: i32) -> bool {
-    (year % 4 == 0 && year % 100 != 0) || (year % 400 == 0)
+pub fn parse_position(position: &str) -> Result<Square, ChessError> {
+    position.parse()
 }
 
 
-fn days_in_month(year: i32, month: i32) -> i32 {
-    match month {
-        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
-        4 | 6 | 9 | 11 => 30,
-        2 => if is_leap_year(year) { 29 } else { 28 },
-        _ => 0,
+pub fn parse_promotion(letter: &str, player: Player) -> Result<Piece, ChessError> {
+    match letter.to_ascii_lowercase().as_str() {
+        "q" => Ok(Piece::Queen(player)),
+        "r" => Ok(Piece::Rook(player)),
+        "b" => Ok(Piece::Bishop(player)),
+        "n" => Ok(Piece::Knight(player)),
+        _ => Err(ChessError::InvalidNotation(letter.to_string())),
     }
 }
 
-
-fn today() -> String {
-    let now = SystemTime::now();
-    let duration_since_epoch = now.duration_since(UNIX_EPOCH).unwrap();
-    let seconds = duration_since_epoch.as_secs();
-    
-    // Calculate days since the epoch (1970-01-01)
-    let days_since_epoch = seconds / 86400; // 86400 seconds per day
-    
-    // Calculate the year
-    let mut year = 1970;
-    let mut days_in_year = 365;
-    
-    // Loop through years to find the correct year
-    let mut remaining_days = days_since_epoch as i32;
-    while remaining_days >= days_in_year {
-        if is_leap_year(year) {
-            days_in_year = 366;
-        } else {
-            days_in_year = 365;
-        }
-        
-        if remaining_days < days_in_year {
-            break;
-        }
-        
-        remaining_days -= days_in_year;
-        year += 1;
-    }
-
-    // Now find the month and day
-    let mut month = 1;
-    let mut days_in_current_month = days_in_month(year, month);
-    
-    while remaining_days >= days_in_current_month {
-        remaining_days -= days_in_current_month;
-        month += 1;
-        days_in_current_month = days_in_month(year, month);
-    }
-
-    let day = remaining_days + 1; // The remaining days are the current day of the month
-    
-    // Output the result
-    format!("{year}-{month}-{day}")
-}
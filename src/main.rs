@@ -1,55 +1,160 @@
 use std::io::{self, Write};
-use chessme::{ChessBoard, Player, parse_position}; // Re-export game structs to be accessible
+use std::env;
+use std::time::Instant;
+use chessme::{ChessError, Game, Position, parse_position, parse_promotion}; // Re-export game structs to be accessible
 
 
 fn main() {
-    let mut board = ChessBoard::new();
-    let mut current_player = Player::White;
+    // `chessme perft <depth> [fen]` counts the moves from the given or the initial position and exits.
+    let args: Vec<String> = env::args().collect();
+    if args.len() >= 3 && args[1] == "perft" {
+        let position = if args.len() > 3 { Position::from_fen(&args[3..].join(" ")) } else { Ok(Position::new()) };
+        let depth = args[2].parse::<u32>().ok().filter(|depth| *depth > 0);
+        match (depth, position) {
+            (Some(depth), Ok(position)) => print_perft(&position, depth),
+            (None, _) => println!("Invalid depth: {}", args[2]),
+            (_, Err(err)) => println!("Error: {}", err),
+        }
+        return;
+    }
 
-    // Example to track moves and write PGN
-    let white_player = "Player 1";
-    let black_player = "Player 2";
+    let mut game = Game::new();
+    game.set_players("Player 1", "Player 2");
 
     loop {
-        board.print();
-        println!("{:?}'s turn", current_player);
+        let position = game.board().position();
+        position.print();
+
+        if let Some(outcome) = game.outcome() {
+            println!("{}", outcome);
+            break;
+        }
+        println!("{:?}'s turn", position.side_to_move());
 
         // Read user input
         let mut input = String::new();
-        print!("Enter move (e.g., 'e2 e4'), 'stop' to stop the game: ");
+        print!("Enter move (e.g., 'e2 e4', 'e2e4', 'Nf3' or 'e7e8q'), 'moves' to list legal moves, 'perft 3' to count moves, 'undo', 'redo', 'resign', 'draw' to agree to a draw, 'claim' to claim a draw, 'stop' to stop the game: ");
         io::stdout().flush().unwrap();
         io::stdin().read_line(&mut input).unwrap();
 
-        let parts: Vec<&str> = input.trim().split_whitespace().collect();
+        let parts: Vec<&str> = input.split_whitespace().collect();
 
         if parts.len() == 1 && parts[0] == "stop" {
             println!("Stopping the game.");
             break;
         }
 
-        if parts.len() != 2 {
-            println!("Invalid input. Please enter in format 'e2 e4'.");
+        if parts.len() == 1 && (parts[0] == "undo" || parts[0] == "redo") {
+            let replayed = if parts[0] == "undo" { game.undo() } else { game.redo() };
+            if replayed.is_none() {
+                println!("Nothing to {}.", parts[0]);
+            }
             continue;
         }
 
-        let start = parse_position(parts[0]);
-        let end = parse_position(parts[1]);
+        if parts.len() == 1 && parts[0] == "resign" {
+            let player = position.side_to_move();
+            game.resign(player);
+            continue;
+        }
 
-        match (start, end) {
-            (Some(start_pos), Some(end_pos)) if board.move_if_valid(start_pos, end_pos, current_player) => {
-                if let Err(err) = board.move_piece(start_pos, end_pos) {
-                    println!("Error: {}", err);
-                    continue;
-                }           
-                current_player = board.next_player(current_player);
+        if parts.len() == 1 && parts[0] == "claim" {
+            if game.claim_draw().is_none() {
+                println!("No draw can be claimed in this position.");
+            }
+            continue;
+        }
+
+        if parts.len() == 1 && parts[0] == "draw" {
+            game.agree_draw();
+            continue;
+        }
+
+        if parts.len() == 1 && parts[0] == "moves" {
+            let moves: Vec<String> = position.legal_moves()
+                .iter()
+                .map(|m| m.to_uci())
+                .collect();
+            println!("Legal moves: {}", moves.join(", "));
+            continue;
+        }
+
+        if parts.len() == 2 && parts[0] == "perft" {
+            match parts[1].parse::<u32>() {
+                Ok(depth) if depth > 0 => print_perft(position, depth),
+                _ => println!("Invalid depth: {}", parts[1]),
+            }
+            print!("Press enter to go back to the board.");
+            io::stdout().flush().unwrap();
+            io::stdin().read_line(&mut String::new()).unwrap();
+            continue;
+        }
+
+        // Anything else on its own is a move in UCI, like 'e7e8q', or in SAN.
+        if parts.len() == 1 {
+            // Two squares are UCI, its error stands: SAN would take "e7e8" as a promotion to a queen.
+            let played = match game.play_uci(parts[0]) {
+                Err(ChessError::InvalidNotation(_)) if !is_coordinate_move(parts[0]) => game.play_san(parts[0]),
+                played => played,
+            };
+            if let Err(err) = played {
+                println!("Error: {}. Try again.", err);
+            }
+            continue;
+        }
+
+        if parts.len() != 2 && parts.len() != 3 {
+            println!("Invalid input. Please enter in format 'e2 e4', 'Nf3' or 'e7 e8 q'.");
+            continue;
+        }
+
+        let (start, end) = match (parse_position(parts[0]), parse_position(parts[1])) {
+            (Ok(start), Ok(end)) => (start, end),
+            (Err(err), _) | (_, Err(err)) => {
+                println!("Error: {}. Try again.", err);
+                continue;
             }
-            _ => println!("Invalid move. Try again."),
+        };
+
+        // Optional promotion piece: q, r, b or n.
+        let promotion = match parts.get(2) {
+            Some(letter) => match parse_promotion(letter, position.side_to_move()) {
+                Ok(piece) => Some(piece),
+                Err(_) => {
+                    println!("Invalid promotion piece. Use one of 'q', 'r', 'b' or 'n'.");
+                    continue;
+                }
+            },
+            None => None,
+        };
+
+        if let Err(err) = game.play_squares(start, end, promotion) {
+            println!("Error: {}. Try again.", err);
         }
     }
 
     // Write PGN to string
-    let result = board.result(current_player);
-    let pgn:String = board.write_to_pgn(white_player, black_player, &result);
+    let pgn:String = game.to_pgn();
     println!("\nPGN:\n{}", pgn);
 }
 
+
+
+// Prints the node count under each legal move, then the total.
+fn print_perft(position: &Position, depth: u32) {
+    let start = Instant::now();
+    let mut total: u64 = 0;
+    for (mv, nodes) in position.divide(depth) {
+        println!("{}: {}", mv.to_uci(), nodes);
+        total += nodes;
+    }
+    println!("\nNodes searched: {} in {:.2?}", total, start.elapsed());
+}
+
+
+// Shaped like a UCI move: two squares, maybe followed by a promotion letter.
+fn is_coordinate_move(input: &str) -> bool {
+    (input.len() == 4 || input.len() == 5)
+        && input.get(0..2).is_some_and(|square| parse_position(square).is_ok())
+        && input.get(2..4).is_some_and(|square| parse_position(square).is_ok())
+}
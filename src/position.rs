@@ -0,0 +1,1093 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::{ChessError, File, Move, MoveKind, Piece, Player, Rank, Square};
+use crate::bitboard::{Bitboard, between, bishop_attacks, king_attacks, knight_attacks, pawn_attacks, queen_attacks, rook_attacks};
+use crate::zobrist::{castling_key, en_passant_key, piece_key, turn_key};
+
+
+// Which castles are still available, lost once the king or the matching rook moves or the rook is captured.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct CastlingRights {
+    pub white_king_side: bool,
+    pub white_queen_side: bool,
+    pub black_king_side: bool,
+    pub black_queen_side: bool,
+}
+
+impl CastlingRights {
+    pub fn all() -> Self {
+        CastlingRights { white_king_side: true, white_queen_side: true, black_king_side: true, black_queen_side: true }
+    }
+
+    pub fn none() -> Self {
+        CastlingRights { white_king_side: false, white_queen_side: false, black_king_side: false, black_queen_side: false }
+    }
+
+    pub fn king_side(&self, player: Player) -> bool {
+        match player {
+            Player::White => self.white_king_side,
+            Player::Black => self.black_king_side,
+        }
+    }
+
+    pub fn queen_side(&self, player: Player) -> bool {
+        match player {
+            Player::White => self.white_queen_side,
+            Player::Black => self.black_queen_side,
+        }
+    }
+}
+
+// Castling availability as written in FEN, e.g. "KQkq" or "-".
+impl fmt::Display for CastlingRights {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let rights = [
+            (self.white_king_side, 'K'),
+            (self.white_queen_side, 'Q'),
+            (self.black_king_side, 'k'),
+            (self.black_queen_side, 'q'),
+        ];
+        let symbols: String = rights.iter().filter(|(right, _)| *right).map(|(_, symbol)| *symbol).collect();
+        if symbols.is_empty() {
+            write!(f, "-")
+        } else {
+            write!(f, "{}", symbols)
+        }
+    }
+}
+
+// State a move overwrites, needed to take it back along with the move itself.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct UndoInfo {
+    pub castling_rights: CastlingRights,
+    pub en_passant: Option<Square>,
+    pub halfmove_clock: u32,
+    pub fullmove_number: u32,
+    pub zobrist: u64,
+}
+
+// When FEN output gives the en passant square.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum EnPassantMode {
+    Always,  // After every double push, as the FEN standard has it
+    Capturable,  // Only when an en passant capture is legal, as in repetition checks
+}
+
+// A piece that can't leave the line between its king and an enemy slider without exposing the king.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Pin {
+    pub square: Square,  // Where the pinned piece stands
+    pub pinner: Square,
+    pub ray: Bitboard,  // Where the pinned piece may still go: the squares between king and pinner, and the pinner
+}
+
+// Everything needed to go on with a game: where the pieces are, whose turn it is,
+// the castling rights, the en passant square and the clocks.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Position {
+    board: [Option<Piece>; 64],  // Indexed by square, a1 first, kept in step with the bitboards
+    pieces: [Bitboard; 12],  // One set per piece type and color, see `piece_index`
+    colors: [Bitboard; 2],  // White pieces, then black pieces
+    side_to_move: Player,
+    castling_rights: CastlingRights,
+    en_passant: Option<Square>,  // Square skipped by a pawn double push on the last move
+    halfmove_clock: u32,  // Half moves since the last capture or pawn move
+    fullmove_number: u32,  // Starts at 1, incremented after each Black move
+    zobrist: u64,  // Polyglot key, updated along with the rest of the position
+}
+
+impl Default for Position {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Position {
+    pub fn new() -> Self {
+        let mut position = Position::empty(Player::White, CastlingRights::all());
+        let back_rank = |player| [
+            Piece::Rook(player), Piece::Knight(player), Piece::Bishop(player), Piece::Queen(player),
+            Piece::King(player), Piece::Bishop(player), Piece::Knight(player), Piece::Rook(player),
+        ];
+
+        for (file, piece) in back_rank(Player::White).into_iter().enumerate() {
+            let file = File::new(file).unwrap();
+
+            // Place white pieces
+            position.put_piece(Square::new(file, Rank::FIRST), piece);
+            position.put_piece(Square::new(file, Rank::SECOND), Piece::Pawn(Player::White));
+
+            // Place black pieces
+            position.put_piece(Square::new(file, Rank::EIGHTH), back_rank(Player::Black)[file.index()]);
+            position.put_piece(Square::new(file, Rank::SEVENTH), Piece::Pawn(Player::Black));
+        }
+
+        position
+    }
+
+    // Sets up the given pieces on an empty board, with no castling rights.
+    pub fn from_pieces(pieces: &[(Square, Piece)], side_to_move: Player) -> Self {
+        let mut position = Position::empty(side_to_move, CastlingRights::none());
+        for (square, piece) in pieces {
+            position.put_piece(*square, *piece);
+        }
+        position
+    }
+
+    // Reads the six fields of a FEN record, all of them required.
+    pub fn from_fen(fen: &str) -> Result<Self, ChessError> {
+        let fields:Vec<&str> = fen.split_whitespace().collect();
+        if fields.len() != 6 {
+            return Err(ChessError::InvalidFen(format!("expected 6 fields, found {}", fields.len())));
+        }
+
+        let side_to_move:Player = match fields[1] {
+            "w" => Player::White,
+            "b" => Player::Black,
+            other => return Err(ChessError::InvalidFen(format!("invalid side to move '{}'", other))),
+        };
+        let castling_rights:CastlingRights = parse_castling_rights(fields[2])?;
+        let mut position = Position::empty(side_to_move, castling_rights);
+
+        // Piece placement, from the eighth rank down to the first.
+        let ranks:Vec<&str> = fields[0].split('/').collect();
+        if ranks.len() != 8 {
+            return Err(ChessError::InvalidFen(format!("expected 8 ranks, found {}", ranks.len())));
+        }
+        for (row, squares) in ranks.iter().enumerate() {
+            let rank:Rank = Rank::new(7 - row).unwrap();
+            let mut file:usize = 0;
+            let mut after_digit:bool = false;
+            for symbol in squares.chars() {
+                if let Some(empty) = symbol.to_digit(10).filter(|n| (1..=8).contains(n)) {
+                    // Empty squares in a row are counted by a single digit.
+                    if after_digit {
+                        return Err(ChessError::InvalidFen(format!("adjacent digits on rank {}", rank)));
+                    }
+                    after_digit = true;
+                    file += empty as usize;
+                } else {
+                    after_digit = false;
+                    let piece:Piece = piece_from_symbol(symbol)
+                        .ok_or_else(|| ChessError::InvalidFen(format!("invalid piece '{}' on rank {}", symbol, rank)))?;
+                    if let Some(file) = File::new(file) {
+                        position.put_piece(Square::new(file, rank), piece);
+                    }
+                    file += 1;
+                }
+                if file > 8 {
+                    break;
+                }
+            }
+            if file != 8 {
+                return Err(ChessError::InvalidFen(format!("rank {} has {} squares instead of 8", rank, file)));
+            }
+        }
+        position.check_setup()?;
+
+        if fields[3] != "-" {
+            let square:Square = fields[3].parse()
+                .map_err(|_| ChessError::InvalidFen(format!("invalid en passant square '{}'", fields[3])))?;
+            if !position.is_en_passant_target(square) {
+                return Err(ChessError::InvalidFen(format!("no pawn can be taken en passant on {}", square)));
+            }
+            position.en_passant = Some(square);
+            position.zobrist ^= position.capturable_en_passant_key();
+        }
+
+        // Plain digits only, `parse` would also take a sign.
+        let is_number = |field: &str| !field.is_empty() && field.bytes().all(|b| b.is_ascii_digit());
+        position.halfmove_clock = Some(fields[4]).filter(|field| is_number(field)).and_then(|field| field.parse().ok())
+            .ok_or_else(|| ChessError::InvalidFen(format!("invalid halfmove clock '{}'", fields[4])))?;
+        position.fullmove_number = Some(fields[5]).filter(|field| is_number(field)).and_then(|field| field.parse().ok()).filter(|n| *n > 0)
+            .ok_or_else(|| ChessError::InvalidFen(format!("invalid fullmove number '{}'", fields[5])))?;
+
+        Ok(position)
+    }
+
+    // Rejects placements no game can reach or go on from.
+    fn check_setup(&self) -> Result<(), ChessError> {
+        for player in [Player::White, Player::Black] {
+            let kings:u32 = self.pieces(Piece::King(player)).count();
+            if kings != 1 {
+                return Err(ChessError::InvalidFen(format!("{:?} has {} kings", player, kings)));
+            }
+        }
+        let pawns:Bitboard = self.pieces(Piece::Pawn(Player::White)) | self.pieces(Piece::Pawn(Player::Black));
+        if let Some(square) = pawns.squares().find(|square| is_last_rank(*square)) {
+            return Err(ChessError::InvalidFen(format!("pawn on {}", square)));
+        }
+        if self.is_king_attacked(self.side_to_move.opponent()) {
+            return Err(ChessError::InvalidFen(format!("{:?} is in check but not to move", self.side_to_move.opponent())));
+        }
+
+        // Each castling right needs the king and the rook still at home.
+        let rights = [
+            (self.castling_rights.white_king_side, 'K', Player::White, File::H),
+            (self.castling_rights.white_queen_side, 'Q', Player::White, File::A),
+            (self.castling_rights.black_king_side, 'k', Player::Black, File::H),
+            (self.castling_rights.black_queen_side, 'q', Player::Black, File::A),
+        ];
+        for (right, symbol, player, rook_file) in rights {
+            let in_place:bool = self.board[Square::new(File::E, home_rank(player)).index()] == Some(Piece::King(player))
+                && self.board[Square::new(rook_file, home_rank(player)).index()] == Some(Piece::Rook(player));
+            if right && !in_place {
+                return Err(ChessError::InvalidFen(format!("castling right '{}' without the king and rook in place", symbol)));
+            }
+        }
+        Ok(())
+    }
+
+    // Whether the opponent's last move can have been a double push skipping over the square.
+    fn is_en_passant_target(&self, square: Square) -> bool {
+        let opponent:Player = self.side_to_move.opponent();
+        let (rank, direction):(Rank, isize) = match opponent {
+            Player::White => (Rank::THIRD, 1),
+            Player::Black => (Rank::SIXTH, -1),
+        };
+        let pushed_pawn:Option<Piece> = square.offset(0, direction).and_then(|pawn| self.board[pawn.index()]);
+        let start_is_empty:bool = square.offset(0, -direction).is_some_and(|start| self.board[start.index()].is_none());
+        square.rank() == rank && self.board[square.index()].is_none() && start_is_empty && pushed_pawn == Some(Piece::Pawn(opponent))
+    }
+
+    fn empty(side_to_move: Player, castling_rights: CastlingRights) -> Self {
+        Position {
+            board: [None; 64],
+            pieces: [Bitboard::EMPTY; 12],
+            colors: [Bitboard::EMPTY; 2],
+            side_to_move,
+            castling_rights,
+            en_passant: None,
+            halfmove_clock: 0,
+            fullmove_number: 1,
+            zobrist: castling_key(castling_rights) ^ if side_to_move == Player::White { turn_key() } else { 0 },
+        }
+    }
+
+    fn put_piece(&mut self, square: Square, piece: Piece) {
+        self.remove_piece(square);
+        self.board[square.index()] = Some(piece);
+        self.pieces[piece_index(piece)] |= Bitboard::from_square(square);
+        self.colors[player_index(piece.player())] |= Bitboard::from_square(square);
+        self.zobrist ^= piece_key(square, piece);
+    }
+
+    fn remove_piece(&mut self, square: Square) -> Option<Piece> {
+        let piece:Piece = self.board[square.index()].take()?;
+        self.pieces[piece_index(piece)] &= !Bitboard::from_square(square);
+        self.colors[player_index(piece.player())] &= !Bitboard::from_square(square);
+        self.zobrist ^= piece_key(square, piece);
+        Some(piece)
+    }
+
+    // Squares holding the given piece.
+    pub fn pieces(&self, piece: Piece) -> Bitboard {
+        self.pieces[piece_index(piece)]
+    }
+
+    pub fn occupied_by(&self, player: Player) -> Bitboard {
+        self.colors[player_index(player)]
+    }
+
+    pub fn occupied(&self) -> Bitboard {
+        self.colors[0] | self.colors[1]
+    }
+
+    pub fn piece_at(&self, square: Square) -> Option<Piece> {
+        self.board[square.index()]
+    }
+
+    pub fn side_to_move(&self) -> Player {
+        self.side_to_move
+    }
+
+    pub fn castling_rights(&self) -> CastlingRights {
+        self.castling_rights
+    }
+
+    pub fn en_passant(&self) -> Option<Square> {
+        self.en_passant
+    }
+
+    pub fn halfmove_clock(&self) -> u32 {
+        self.halfmove_clock
+    }
+
+    pub fn fullmove_number(&self) -> u32 {
+        self.fullmove_number
+    }
+
+    // Identifies the position in a single number, the same one Polyglot opening books use.
+    pub fn zobrist_key(&self) -> u64 {
+        self.zobrist
+    }
+
+    // The Zobrist key as repetitions see it: the en passant square only counts when taking on it is legal.
+    pub fn repetition_key(&self) -> u64 {
+        match self.en_passant {
+            Some(square) if self.can_capture_en_passant() && !self.has_legal_en_passant() => self.zobrist ^ en_passant_key(square.file()),
+            _ => self.zobrist,
+        }
+    }
+
+    pub fn print(&self) {
+        print!("{}[2J", 27 as char);
+        for row in self.board.chunks(8).rev() {
+            for cell in row.iter() {
+                match cell {
+                    Some(piece) => {
+                        let symbol = match piece {
+                            Piece::King(player) => if *player == Player::White { "♔" } else { "♚" },
+                            Piece::Queen(player) => if *player == Player::White { "♕" } else { "♛" },
+                            Piece::Rook(player) => if *player == Player::White { "♖" } else { "♜" },
+                            Piece::Bishop(player) => if *player == Player::White { "♗" } else { "♝" },
+                            Piece::Knight(player) => if *player == Player::White { "♘" } else { "♞" },
+                            Piece::Pawn(player) => if *player == Player::White { "♙" } else { "♟" },
+                        };
+                        print!("{} ", symbol);
+                    },
+                    None => print!(". "),
+                }
+            }
+            println!();
+        }
+    }
+
+    // Applies a move and hands the turn to the opponent.
+    // The move is expected to come from this position, as `build_move` and the generator make them.
+    pub fn make_move(&mut self, mv: Move) -> UndoInfo {
+        let (start, end) = (mv.from, mv.to);
+        let undo = UndoInfo {
+            castling_rights: self.castling_rights,
+            en_passant: self.en_passant,
+            halfmove_clock: self.halfmove_clock,
+            fullmove_number: self.fullmove_number,
+            zobrist: self.zobrist,
+        };
+        self.zobrist ^= castling_key(self.castling_rights) ^ self.capturable_en_passant_key();
+
+        // Captures and pawn moves are irreversible and restart the fifty-move count.
+        if matches!(mv.piece, Piece::Pawn(_)) || mv.is_capture() {
+            self.halfmove_clock = 0;
+        } else {
+            self.halfmove_clock += 1;
+        }
+        if self.side_to_move == Player::Black {
+            self.fullmove_number += 1;
+        }
+
+        // En passant: the captured pawn stands next to the start square, not on the end square.
+        if mv.is_en_passant() {
+            self.remove_piece(en_passant_victim(mv));
+        }
+
+        self.remove_piece(start);
+        self.put_piece(end, mv.promotion.unwrap_or(mv.piece));
+
+        // Castling: the king moves two squares and the rook jumps over it.
+        if mv.is_castle() {
+            let (rook_start, rook_end) = castling_rook_squares(end);
+            if let Some(rook) = self.remove_piece(rook_start) {
+                self.put_piece(rook_end, rook);
+            }
+        }
+
+        self.update_castling_rights(start, end);
+
+        self.en_passant = match mv.kind {
+            MoveKind::DoublePush => start.offset(0, (end.rank().index() as isize - start.rank().index() as isize) / 2),
+            _ => None,
+        };
+        self.side_to_move = self.side_to_move.opponent();
+        self.zobrist ^= castling_key(self.castling_rights) ^ self.capturable_en_passant_key() ^ turn_key();
+
+        undo
+    }
+
+    // Reverts `make_move`, given the same move and what it returned.
+    pub fn unmake_move(&mut self, mv: Move, undo: UndoInfo) {
+        let (start, end) = (mv.from, mv.to);
+        self.remove_piece(end);
+        self.put_piece(start, mv.piece);
+
+        if mv.is_castle() {
+            let (rook_start, rook_end) = castling_rook_squares(end);
+            if let Some(rook) = self.remove_piece(rook_end) {
+                self.put_piece(rook_start, rook);
+            }
+        }
+
+        if let Some(captured) = mv.captured {
+            let square:Square = if mv.is_en_passant() { en_passant_victim(mv) } else { end };
+            self.put_piece(square, captured);
+        }
+
+        self.side_to_move = self.side_to_move.opponent();
+        self.castling_rights = undo.castling_rights;
+        self.en_passant = undo.en_passant;
+        self.halfmove_clock = undo.halfmove_clock;
+        self.fullmove_number = undo.fullmove_number;
+        self.zobrist = undo.zobrist;
+    }
+
+    // Completes a move between two squares with what it moves, takes and does.
+    pub fn build_move(&self, from: Square, to: Square, promotion: Option<Piece>) -> Option<Move> {
+        let piece:Piece = self.board[from.index()]?;
+        let promotion:Option<Piece> = match piece {
+            Piece::Pawn(player) if is_last_rank(to) => Some(promotion_or_queen(promotion, player)),
+            _ => None,
+        };
+        Some(self.new_move(from, to, piece, promotion))
+    }
+
+    fn new_move(&self, from: Square, to: Square, piece: Piece, promotion: Option<Piece>) -> Move {
+        let mut captured:Option<Piece> = self.board[to.index()];
+        let kind:MoveKind = match piece {
+            Piece::King(player) if self.is_castling(from, to, player) => MoveKind::Castle,
+            Piece::Pawn(_) if from.rank().index().abs_diff(to.rank().index()) == 2 => MoveKind::DoublePush,
+            Piece::Pawn(_) if from.file() != to.file() && captured.is_none() && self.en_passant == Some(to) => {
+                captured = self.board[Square::new(to.file(), from.rank()).index()];
+                MoveKind::EnPassant
+            },
+            _ => MoveKind::Normal,
+        };
+        Move { from, to, piece, captured, promotion, kind }
+    }
+
+    // The king going two squares from e1 or e8 towards its rook, still in the corner.
+    fn is_castling(&self, from: Square, to: Square, player: Player) -> bool {
+        let rank:Rank = home_rank(player);
+        if from != Square::new(File::E, rank) || !(to == Square::new(File::G, rank) || to == Square::new(File::C, rank)) {
+            return false;
+        }
+        let (rook_start, _) = castling_rook_squares(to);
+        self.board[rook_start.index()] == Some(Piece::Rook(player))
+    }
+
+    fn update_castling_rights(&mut self, start: Square, end: Square) {
+        // Anything leaving or landing on a king or rook initial square removes the matching rights.
+        for square in [start, end] {
+            match (square.file(), square.rank()) {
+                (File::E, Rank::FIRST) => {
+                    self.castling_rights.white_king_side = false;
+                    self.castling_rights.white_queen_side = false;
+                },
+                (File::A, Rank::FIRST) => self.castling_rights.white_queen_side = false,
+                (File::H, Rank::FIRST) => self.castling_rights.white_king_side = false,
+                (File::E, Rank::EIGHTH) => {
+                    self.castling_rights.black_king_side = false;
+                    self.castling_rights.black_queen_side = false;
+                },
+                (File::A, Rank::EIGHTH) => self.castling_rights.black_queen_side = false,
+                (File::H, Rank::EIGHTH) => self.castling_rights.black_king_side = false,
+                _ => {},
+            }
+        }
+    }
+
+    // The legal move between two squares, or why there is none.
+    pub fn validate_move(&self, start: Square, end: Square, promotion: Option<Piece>) -> Result<Move, ChessError> {
+        let piece:Piece = self.board[start.index()].ok_or(ChessError::NoPieceOnSquare(start))?;
+        if piece.player() != self.side_to_move {
+            return Err(ChessError::WrongSide { square: start, side_to_move: self.side_to_move });
+        }
+        if let Some(promoted) = promotion {
+            let player:Player = piece.player();
+            if ![Piece::Queen(player), Piece::Rook(player), Piece::Bishop(player), Piece::Knight(player)].contains(&promoted) {
+                return Err(ChessError::InvalidPromotion(promoted));
+            }
+        }
+
+        let mv:Move = self.new_move(start, end, piece, None);
+        let candidates:Vec<Move> = self.pseudo_legal_moves()
+            .into_iter()
+            .filter(|m| m.from == start && m.to == end)
+            .collect();
+        if candidates.is_empty() {
+            if self.is_blocked(mv) {
+                return Err(ChessError::BlockedPath { from: start, to: end });
+            }
+            return Err(ChessError::IllegalMove { from: start, to: end });
+        }
+
+        let mv:Move = match (candidates[0].promotion, promotion) {
+            (Some(_), _) => Move { promotion: Some(promotion_or_queen(promotion, piece.player())), ..candidates[0] },
+            (None, Some(promoted)) => return Err(ChessError::InvalidPromotion(promoted)),
+            (None, None) => candidates[0],
+        };
+
+        let mut position:Position = *self;
+        if !position.is_king_safe_after(mv) {
+            return Err(ChessError::LeavesKingInCheck { from: start, to: end });
+        }
+        Ok(mv)
+    }
+
+    // Whether a queen, rook or bishop lines up with its target but something stands in between.
+    fn is_blocked(&self, mv: Move) -> bool {
+        let (file_distance, rank_distance) = distances(mv.from, mv.to);
+        let is_straight:bool = file_distance == 0 || rank_distance == 0;
+        let is_diagonal:bool = file_distance == rank_distance;
+        let lines_up:bool = match mv.piece {
+            Piece::Queen(_) => is_straight || is_diagonal,
+            Piece::Rook(_) => is_straight,
+            Piece::Bishop(_) => is_diagonal,
+            _ => false,
+        };
+        lines_up && mv.from != mv.to && !self.is_path_clear(mv.from, mv.to)
+    }
+
+    pub fn is_valid_move(&self, start: Square, end: Square) -> bool {
+        self.legal_moves().iter().any(|m| m.from == start && m.to == end)
+    }
+
+    pub fn legal_moves(&self) -> Vec<Move> {
+        // Try every move on a single scratch position, taking each one back afterwards.
+        let mut position:Position = *self;
+        self.pseudo_legal_moves()
+            .into_iter()
+            .filter(|m| position.is_king_safe_after(*m))
+            .collect()
+    }
+
+    pub fn legal_captures(&self) -> Vec<Move> {
+        self.legal_moves()
+            .into_iter()
+            .filter(|m| m.is_capture())
+            .collect()
+    }
+
+    pub fn pseudo_legal_moves(&self) -> Vec<Move> {
+        let player:Player = self.side_to_move;
+        let mut moves:Vec<Move> = Vec::new();
+
+        let (own, occupied) = (self.occupied_by(player), self.occupied());
+
+        for start in own.squares() {
+            let piece:Piece = match self.board[start.index()] {
+                Some(piece) => piece,
+                None => continue,
+            };
+
+            match piece {
+                Piece::King(_) => {
+                    self.add_moves_to(start, king_attacks(start) & !own, piece, &mut moves);
+                    self.add_castling_moves(start, piece, &mut moves);
+                },
+                Piece::Queen(_) => self.add_moves_to(start, queen_attacks(start, occupied) & !own, piece, &mut moves),
+                Piece::Rook(_) => self.add_moves_to(start, rook_attacks(start, occupied) & !own, piece, &mut moves),
+                Piece::Bishop(_) => self.add_moves_to(start, bishop_attacks(start, occupied) & !own, piece, &mut moves),
+                Piece::Knight(_) => self.add_moves_to(start, knight_attacks(start) & !own, piece, &mut moves),
+                Piece::Pawn(_) => self.add_pawn_moves(start, piece, &mut moves),
+            }
+        }
+
+        moves
+    }
+
+    fn is_king_safe_after(&mut self, mv: Move) -> bool {
+        let player:Player = self.side_to_move;
+        let undo:UndoInfo = self.make_move(mv);
+        let is_safe:bool = !self.is_king_attacked(player);
+        self.unmake_move(mv, undo);
+        is_safe
+    }
+
+    fn add_moves_to(&self, start: Square, targets: Bitboard, piece: Piece, moves: &mut Vec<Move>) {
+        for end in targets.squares() {
+            moves.push(self.new_move(start, end, piece, None));
+        }
+    }
+
+    fn add_castling_moves(&self, start: Square, piece: Piece, moves: &mut Vec<Move>) {
+        for end_file in [File::G, File::C] {
+            let end = Square::new(end_file, start.rank());
+            if self.can_castle(start, end) {
+                moves.push(self.new_move(start, end, piece, None));
+            }
+        }
+    }
+
+    fn add_pawn_moves(&self, start: Square, piece: Piece, moves: &mut Vec<Move>) {
+        let player:Player = piece.player();
+        let (direction, initial_rank):(isize, Rank) = match player {
+            Player::White => (1, Rank::SECOND),
+            Player::Black => (-1, Rank::SEVENTH),
+        };
+
+        // Pawn moves 1 square towards opponent, or 2 from its initial position.
+        if let Some(end) = start.offset(0, direction) {
+            if self.board[end.index()].is_none() {
+                self.add_pawn_move(start, end, piece, moves);
+
+                if let Some(double_end) = start.offset(0, 2 * direction) {
+                    if start.rank() == initial_rank && self.board[double_end.index()].is_none() {
+                        moves.push(self.new_move(start, double_end, piece, None));
+                    }
+                }
+            }
+        }
+
+        // Pawn captures diagonally, including en passant.
+        for side in [-1, 1] {
+            if let Some(end) = start.offset(side, direction) {
+                let can_capture:bool = match self.board[end.index()] {
+                    Some(piece) => piece.player() != player,
+                    None => self.en_passant == Some(end),
+                };
+                if can_capture {
+                    self.add_pawn_move(start, end, piece, moves);
+                }
+            }
+        }
+    }
+
+    // Adds the move, once per possible promotion piece when it reaches the last rank.
+    fn add_pawn_move(&self, start: Square, end: Square, piece: Piece, moves: &mut Vec<Move>) {
+        let player:Player = piece.player();
+        if is_last_rank(end) {
+            for promotion in [Piece::Queen(player), Piece::Rook(player), Piece::Bishop(player), Piece::Knight(player)] {
+                moves.push(self.new_move(start, end, piece, Some(promotion)));
+            }
+        } else {
+            moves.push(self.new_move(start, end, piece, None));
+        }
+    }
+
+    pub fn can_castle(&self, start: Square, end: Square) -> bool {
+        let player:Player = match self.board[start.index()] {
+            Some(Piece::King(player)) => player,
+            _ => return false,
+        };
+        let home_rank:Rank = match player {
+            Player::White => Rank::FIRST,
+            Player::Black => Rank::EIGHTH,
+        };
+        if start != Square::new(File::E, home_rank) || end.rank() != home_rank {
+            return false;
+        }
+
+        // Files of the rook, of the squares to be emptied and of the squares the king crosses.
+        let (has_right, rook_file, between, king_path): (bool, File, &[File], [File; 3]) = match end.file() {
+            File::G => (self.castling_rights.king_side(player), File::H, &[File::F, File::G], [File::E, File::F, File::G]),
+            File::C => (self.castling_rights.queen_side(player), File::A, &[File::B, File::C, File::D], [File::E, File::D, File::C]),
+            _ => return false,
+        };
+        if !has_right || self.board[Square::new(rook_file, home_rank).index()] != Some(Piece::Rook(player)) {
+            return false;
+        }
+        if between.iter().any(|file| self.board[Square::new(*file, home_rank).index()].is_some()) {
+            return false;
+        }
+
+        // The king can't castle out of, through or into check.
+        let opponent:Player = player.opponent();
+        !king_path.iter().any(|file| self.is_square_attacked(Square::new(*file, home_rank), opponent))
+    }
+
+    pub fn is_valid_queen_move(&self, start: Square, end: Square) -> bool {
+        queen_attacks(start, self.occupied()).contains(end)
+    }
+
+    pub fn is_valid_rook_move(&self, start: Square, end: Square) -> bool {
+        rook_attacks(start, self.occupied()).contains(end)
+    }
+
+    pub fn is_valid_bishop_move(&self, start: Square, end: Square) -> bool {
+        bishop_attacks(start, self.occupied()).contains(end)
+    }
+
+    pub fn is_valid_knight_move(&self, start: Square, end: Square) -> bool {
+        knight_attacks(start).contains(end)
+    }
+
+    // Whether every square strictly between two squares on a line is empty.
+    fn is_path_clear(&self, start: Square, end: Square) -> bool {
+        let file_step:isize = (end.file().index() as isize - start.file().index() as isize).signum();
+        let rank_step:isize = (end.rank().index() as isize - start.rank().index() as isize).signum();
+
+        let mut current = start;
+        while let Some(next) = current.offset(file_step, rank_step) {
+            if next == end {
+                return true;
+            }
+            if self.board[next.index()].is_some() {
+                return false;
+            }
+            current = next;
+        }
+        false
+    }
+
+    // Whether a piece of `by` attacks the square, regardless of what stands on it.
+    pub fn is_square_attacked(&self, square: Square, by: Player) -> bool {
+        !self.attackers_by(square, by).is_empty()
+    }
+
+    // Pieces of either side attacking the square.
+    pub fn attackers_of(&self, square: Square) -> Bitboard {
+        self.attackers_by(square, Player::White) | self.attackers_by(square, Player::Black)
+    }
+
+    fn attackers_by(&self, square: Square, by: Player) -> Bitboard {
+        let occupied:Bitboard = self.occupied();
+        let queens:Bitboard = self.pieces(Piece::Queen(by));
+
+        // A square is attacked by a piece if that piece would attack it back from the square.
+        (pawn_attacks(square, by.opponent()) & self.pieces(Piece::Pawn(by)))
+            | (knight_attacks(square) & self.pieces(Piece::Knight(by)))
+            | (king_attacks(square) & self.pieces(Piece::King(by)))
+            | (bishop_attacks(square, occupied) & (self.pieces(Piece::Bishop(by)) | queens))
+            | (rook_attacks(square, occupied) & (self.pieces(Piece::Rook(by)) | queens))
+    }
+
+    pub fn is_opponent(&self, piece:Piece,  player: Player) -> bool {
+        piece.player() != player
+    }
+
+    pub fn write_to_fen(&self) -> String {
+        self.write_to_fen_with(EnPassantMode::Always)
+    }
+
+    pub fn write_to_fen_with(&self, en_passant_mode: EnPassantMode) -> String {
+        let mut fen = String::new();
+
+        // Piece Placement
+        for row in self.board.chunks(8).rev() {
+            let mut empty_count = 0;
+            for cell in row.iter() {
+                match cell {
+                    Some(piece) => {
+                        if empty_count > 0 {
+                            fen.push_str(&empty_count.to_string());
+                            empty_count = 0;
+                        }
+                        fen.push(piece_symbol(*piece));
+                    },
+                    None => {
+                        empty_count += 1;
+                    },
+                }
+            }
+            if empty_count > 0 {
+                fen.push_str(&empty_count.to_string());
+            }
+            fen.push('/');
+        }
+        fen.pop(); // Remove the last '/'.
+
+        // Active color
+        fen.push(' ');
+        fen.push(if self.side_to_move == Player::White { 'w' } else { 'b' });
+
+        // Castling availability
+        fen.push_str(&format!(" {}", self.castling_rights));
+
+        // En passant target square
+        let en_passant:Option<Square> = match en_passant_mode {
+            EnPassantMode::Always => self.en_passant,
+            EnPassantMode::Capturable => self.en_passant.filter(|_| self.has_legal_en_passant()),
+        };
+        match en_passant {
+            Some(square) => fen.push_str(&format!(" {}", square)),
+            None => fen.push_str(" -"),
+        }
+
+        // Halfmove clock and fullmove number
+        fen.push_str(&format!(" {} {}", self.halfmove_clock, self.fullmove_number));
+
+        fen
+    }
+
+    // Dead positions: K vs K, K+minor vs K, or only bishops left, all on squares of the same color.
+    pub fn is_insufficient_material(&self) -> bool {
+        let mut minor_pieces:Vec<(Piece, usize)> = Vec::new();
+
+        for square in Square::all() {
+            match self.board[square.index()] {
+                Some(Piece::King(_)) | None => continue,
+                Some(piece @ (Piece::Bishop(_) | Piece::Knight(_))) => {
+                    minor_pieces.push((piece, (square.file().index() + square.rank().index()) % 2));
+                },
+                Some(_) => return false,
+            }
+        }
+
+        if minor_pieces.len() <= 1 {
+            return true;
+        }
+        let square_color:usize = minor_pieces[0].1;
+        minor_pieces.iter().all(|(piece, color)| matches!(piece, Piece::Bishop(_)) && *color == square_color)
+    }
+
+    pub fn can_claim_fifty_moves(&self) -> bool {
+        self.halfmove_clock >= 100
+    }
+
+    // Readable counterpart of the Zobrist key: placement, side to move, castling rights and en passant square.
+    pub fn position_key(&self) -> String {
+        let fen:String = self.write_to_fen_with(EnPassantMode::Capturable);
+        fen.split(' ').take(4).collect::<Vec<&str>>().join(" ")
+    }
+
+    // Part of the key for the en passant square. Polyglot counts it whenever a pawn stands next to the
+    // pawn that just moved, even if taking would leave the king in check.
+    fn capturable_en_passant_key(&self) -> u64 {
+        match self.en_passant {
+            Some(square) if self.can_capture_en_passant() => en_passant_key(square.file()),
+            _ => 0,
+        }
+    }
+
+    fn has_legal_en_passant(&self) -> bool {
+        self.en_passant.is_some() && self.legal_moves().iter().any(Move::is_en_passant)
+    }
+
+    // The en passant square only matters when a pawn stands ready to take on it.
+    fn can_capture_en_passant(&self) -> bool {
+        let target:Square = match self.en_passant {
+            Some(target) => target,
+            None => return false,
+        };
+        let player:Player = self.side_to_move;
+        !(pawn_attacks(target, player.opponent()) & self.pieces(Piece::Pawn(player))).is_empty()
+    }
+
+    // Whether the side to move is in check.
+    pub fn is_check(&self) -> bool {
+        self.is_king_attacked(self.side_to_move)
+    }
+
+    pub fn is_checkmate(&self) -> bool {
+        self.is_check() && !self.has_legal_moves()
+    }
+
+    pub fn is_stalemate(&self) -> bool {
+        !self.is_check() && !self.has_legal_moves()
+    }
+
+    fn is_king_attacked(&self, player: Player) -> bool {
+        // Find the player's king position
+        let king_position: Square = match self.find_king_position(player){
+            Some(position)=> position,
+            None => return false
+        };
+
+        // Check if any opposing piece attacks the king
+        self.is_square_attacked(king_position, player.opponent())
+    }
+
+    // Enemy pieces giving check to the side to move.
+    pub fn checkers(&self) -> Bitboard {
+        let player:Player = self.side_to_move;
+        match self.find_king_position(player) {
+            Some(king) => self.attackers_by(king, player.opponent()),
+            None => Bitboard::EMPTY,
+        }
+    }
+
+    // Pieces of `player` pinned to their king, whatever side is to move.
+    pub fn pinned(&self, player: Player) -> Vec<Pin> {
+        let king:Square = match self.find_king_position(player) {
+            Some(king) => king,
+            None => return Vec::new(),
+        };
+        let opponent:Player = player.opponent();
+        let queens:Bitboard = self.pieces(Piece::Queen(opponent));
+
+        // Enemy sliders lined up with the king as if the board were empty.
+        let snipers:Bitboard = (rook_attacks(king, Bitboard::EMPTY) & (self.pieces(Piece::Rook(opponent)) | queens))
+            | (bishop_attacks(king, Bitboard::EMPTY) & (self.pieces(Piece::Bishop(opponent)) | queens));
+
+        let mut pins:Vec<Pin> = Vec::new();
+        for pinner in snipers.squares() {
+            let line:Bitboard = between(king, pinner);
+            let blockers:Bitboard = line & self.occupied();
+            // Pinned only when a single piece stands in the way and it belongs to the king's side.
+            if blockers.count() == 1 && !(blockers & self.occupied_by(player)).is_empty() {
+                if let Some(square) = blockers.first() {
+                    pins.push(Pin { square, pinner, ray: (line & !Bitboard::from_square(square)) | Bitboard::from_square(pinner) });
+                }
+            }
+        }
+        pins
+    }
+
+    pub fn find_king_position(&self, player: Player) -> Option<Square> {
+        // None if the king is missing, e.g. in a position set up by hand.
+        self.pieces(Piece::King(player)).first()
+    }
+
+    pub fn has_legal_moves(&self) -> bool {
+        !self.legal_moves().is_empty()
+    }
+
+    // Number of move sequences of the given length, to check the generator against known counts.
+    pub fn perft(&self, depth: u32) -> u64 {
+        let mut position:Position = *self;
+        position.count_nodes(depth)
+    }
+
+    // Perft split by first move, to find which branch a wrong count comes from.
+    // Depth 0 has no first move to split by.
+    pub fn divide(&self, depth: u32) -> Vec<(Move, u64)> {
+        if depth == 0 {
+            return Vec::new();
+        }
+        let mut position:Position = *self;
+        self.legal_moves()
+            .into_iter()
+            .map(|mv| {
+                let undo:UndoInfo = position.make_move(mv);
+                let nodes:u64 = position.count_nodes(depth - 1);
+                position.unmake_move(mv, undo);
+                (mv, nodes)
+            })
+            .collect()
+    }
+
+    fn count_nodes(&mut self, depth: u32) -> u64 {
+        if depth == 0 {
+            return 1;
+        }
+        let moves:Vec<Move> = self.legal_moves();
+        if depth == 1 {
+            return moves.len() as u64;
+        }
+
+        let mut nodes:u64 = 0;
+        for mv in moves {
+            let undo:UndoInfo = self.make_move(mv);
+            nodes += self.count_nodes(depth - 1);
+            self.unmake_move(mv, undo);
+        }
+        nodes
+    }
+}
+
+
+
+impl FromStr for Position {
+    type Err = ChessError;
+
+    fn from_str(fen: &str) -> Result<Self, Self::Err> {
+        Position::from_fen(fen)
+    }
+}
+
+
+// Distances in files and in ranks between two squares.
+fn distances(start: Square, end: Square) -> (usize, usize) {
+    (start.file().index().abs_diff(end.file().index()), start.rank().index().abs_diff(end.rank().index()))
+}
+
+
+// A pawn reaching the last rank becomes a queen unless told otherwise.
+pub(crate) fn promotion_or_queen(promotion: Option<Piece>, player: Player) -> Piece {
+    promotion.unwrap_or(Piece::Queen(player))
+}
+
+
+// Rank the player's king and rooks start on.
+fn home_rank(player: Player) -> Rank {
+    if player == Player::White { Rank::FIRST } else { Rank::EIGHTH }
+}
+
+
+fn is_last_rank(square: Square) -> bool {
+    square.rank() == Rank::FIRST || square.rank() == Rank::EIGHTH
+}
+
+
+// Square of the pawn taken en passant: beside the start square, behind the end square.
+fn en_passant_victim(mv: Move) -> Square {
+    Square::new(mv.to.file(), mv.from.rank())
+}
+
+
+// Squares the rook leaves and lands on when the king castles to `king_end`.
+fn castling_rook_squares(king_end: Square) -> (Square, Square) {
+    let rank:Rank = king_end.rank();
+    if king_end.file() == File::G {
+        (Square::new(File::H, rank), Square::new(File::F, rank))
+    } else {
+        (Square::new(File::A, rank), Square::new(File::D, rank))
+    }
+}
+
+
+// Slot of a piece in `Position::pieces`: kings, queens, rooks, bishops, knights then pawns, white before black.
+fn piece_index(piece: Piece) -> usize {
+    let kind:usize = match piece {
+        Piece::King(_) => 0,
+        Piece::Queen(_) => 1,
+        Piece::Rook(_) => 2,
+        Piece::Bishop(_) => 3,
+        Piece::Knight(_) => 4,
+        Piece::Pawn(_) => 5,
+    };
+    kind * 2 + player_index(piece.player())
+}
+
+
+fn player_index(player: Player) -> usize {
+    match player {
+        Player::White => 0,
+        Player::Black => 1,
+    }
+}
+
+
+// FEN letter of a piece, uppercase for White.
+fn piece_symbol(piece: Piece) -> char {
+    let symbol:char = match piece {
+        Piece::King(_) => 'k',
+        Piece::Queen(_) => 'q',
+        Piece::Rook(_) => 'r',
+        Piece::Bishop(_) => 'b',
+        Piece::Knight(_) => 'n',
+        Piece::Pawn(_) => 'p',
+    };
+    if piece.player() == Player::White { symbol.to_ascii_uppercase() } else { symbol }
+}
+
+
+// Piece for its FEN letter, uppercase for White.
+fn piece_from_symbol(symbol: char) -> Option<Piece> {
+    let player:Player = if symbol.is_ascii_uppercase() { Player::White } else { Player::Black };
+    match symbol.to_ascii_lowercase() {
+        'k' => Some(Piece::King(player)),
+        'q' => Some(Piece::Queen(player)),
+        'r' => Some(Piece::Rook(player)),
+        'b' => Some(Piece::Bishop(player)),
+        'n' => Some(Piece::Knight(player)),
+        'p' => Some(Piece::Pawn(player)),
+        _ => None,
+    }
+}
+
+
+fn parse_castling_rights(field: &str) -> Result<CastlingRights, ChessError> {
+    let mut rights:CastlingRights = CastlingRights::none();
+    if field == "-" {
+        return Ok(rights);
+    }
+    let invalid = || ChessError::InvalidFen(format!("invalid castling rights '{}'", field));
+    for symbol in field.chars() {
+        let right:&mut bool = match symbol {
+            'K' => &mut rights.white_king_side,
+            'Q' => &mut rights.white_queen_side,
+            'k' => &mut rights.black_king_side,
+            'q' => &mut rights.black_queen_side,
+            _ => return Err(invalid()),
+        };
+        *right = true;
+    }
+
+    // Each right at most once, in the order KQkq.
+    if rights.to_string() != field {
+        return Err(invalid());
+    }
+    Ok(rights)
+}
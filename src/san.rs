@@ -0,0 +1,179 @@
+use crate::{ChessError, File, Move, Piece, Player, Position, Rank, Square};
+use crate::position::promotion_or_queen;
+
+
+impl Position {
+    // Standard algebraic notation of a legal move of this position, e.g. "Nbd7", "exd5", "O-O" or "e8=Q+".
+    pub fn to_san(&self, mv: Move) -> String {
+        let mut san:String = if mv.is_castle() {
+            if mv.to.file() == File::G { "O-O".to_string() } else { "O-O-O".to_string() }
+        } else {
+            let mut san = String::new();
+            match mv.piece {
+                Piece::Pawn(_) => {
+                    // Pawn captures name the file the pawn comes from.
+                    if mv.is_capture() {
+                        san.push_str(&mv.from.file().to_string());
+                    }
+                },
+                piece => {
+                    san.push(piece_letter(piece));
+                    san.push_str(&self.disambiguation(mv));
+                },
+            }
+            if mv.is_capture() {
+                san.push('x');
+            }
+            san.push_str(&mv.to.to_string());
+            if let Some(promotion) = mv.promotion {
+                san.push('=');
+                san.push(piece_letter(promotion));
+            }
+            san
+        };
+
+        let mut position:Position = *self;
+        position.make_move(mv);
+        if position.is_checkmate() {
+            san.push('#');
+        } else if position.is_check() {
+            san.push('+');
+        }
+        san
+    }
+
+    // The legal move written in SAN. Sloppy forms are accepted too: "Ng1f3", "e4xd5", "e8Q", "0-0",
+    // a missing promotion piece for a queen and check or annotation marks that don't match.
+    pub fn parse_san(&self, san: &str) -> Result<Move, ChessError> {
+        let invalid = || ChessError::InvalidNotation(san.to_string());
+        let notation:&str = san.trim().trim_end_matches(['+', '#', '!', '?']);
+        let player:Player = self.side_to_move();
+
+        // Castling is the king going two squares towards the rook.
+        let castle_file:Option<File> = match notation {
+            "O-O" | "0-0" => Some(File::G),
+            "O-O-O" | "0-0-0" => Some(File::C),
+            _ => None,
+        };
+        if let Some(file) = castle_file {
+            return self.legal_moves()
+                .into_iter()
+                .find(|m| m.is_castle() && m.to.file() == file)
+                .ok_or_else(|| ChessError::NoMatchingMove(san.to_string()));
+        }
+
+        let mut chars:Vec<char> = notation.chars().collect();
+
+        // Piece letter, uppercase so that 'b' stays a file. Pawns go without.
+        let piece:Piece = match chars.first().and_then(|letter| piece_from_letter(*letter, player)) {
+            Some(piece) => {
+                chars.remove(0);
+                piece
+            },
+            None => Piece::Pawn(player),
+        };
+
+        // Promotion piece after the destination square, with or without '='.
+        let promotion:Option<Piece> = match chars.last().and_then(|letter| piece_from_letter(letter.to_ascii_uppercase(), player)) {
+            Some(promoted) if chars.len() > 2 => {
+                chars.pop();
+                if chars.last() == Some(&'=') {
+                    chars.pop();
+                }
+                Some(promoted)
+            },
+            _ => None,
+        };
+
+        // Destination square, then whatever is left of the start square.
+        if chars.len() < 2 {
+            return Err(invalid());
+        }
+        let to:Square = chars.split_off(chars.len() - 2).iter().collect::<String>().parse().map_err(|_| invalid())?;
+        // 'x' marks a capture, '-' only parts the squares as in "e2-e4". Either needs a piece or a start before it.
+        let separator:Option<char> = chars.pop_if(|c| *c == 'x' || *c == '-');
+        if separator.is_some() && chars.is_empty() && matches!(piece, Piece::Pawn(_)) {
+            return Err(invalid());
+        }
+        let is_capture:bool = separator == Some('x');
+        let origin:Vec<char> = chars;
+        let (from_file, from_rank):(Option<File>, Option<Rank>) = match origin.as_slice() {
+            [] => (None, None),
+            [file @ 'a'..='h'] => (Some(file.to_string().parse().map_err(|_| invalid())?), None),
+            [rank @ '1'..='8'] => (None, Some(rank.to_string().parse().map_err(|_| invalid())?)),
+            [file, rank] => {
+                let from:Square = format!("{}{}", file, rank).parse().map_err(|_| invalid())?;
+                (Some(from.file()), Some(from.rank()))
+            },
+            _ => return Err(invalid()),
+        };
+
+        // Only pawns reaching the last rank promote, and never to a king.
+        if let Some(promoted) = promotion {
+            let last_rank:Rank = if player == Player::White { Rank::EIGHTH } else { Rank::FIRST };
+            if !matches!(piece, Piece::Pawn(_)) || matches!(promoted, Piece::King(_)) || to.rank() != last_rank {
+                return Err(ChessError::InvalidPromotion(promoted));
+            }
+        }
+
+        let mut candidates:Vec<Move> = self.legal_moves()
+            .into_iter()
+            .filter(|m| m.piece == piece && m.to == to && (m.is_capture() || !is_capture))
+            // A pawn named without its file is pushed straight ahead, captures always give the file.
+            .filter(|m| match from_file {
+                Some(file) => m.from.file() == file,
+                None => !matches!(piece, Piece::Pawn(_)) || m.from.file() == to.file(),
+            })
+            .filter(|m| from_rank.is_none_or(|rank| m.from.rank() == rank))
+            .filter(|m| m.promotion.is_none() || m.promotion == Some(promotion_or_queen(promotion, player)))
+            .collect();
+        match candidates.len() {
+            0 => Err(ChessError::NoMatchingMove(san.to_string())),
+            1 => Ok(candidates.remove(0)),
+            _ => Err(ChessError::AmbiguousMove(san.to_string())),
+        }
+    }
+
+    // File, rank or both of the start square, when another piece of the same kind can go to the same square.
+    fn disambiguation(&self, mv: Move) -> String {
+        let rivals:Vec<Move> = self.legal_moves()
+            .into_iter()
+            .filter(|m| m.piece == mv.piece && m.to == mv.to && m.from != mv.from)
+            .collect();
+        if rivals.is_empty() {
+            return String::new();
+        }
+        if rivals.iter().all(|m| m.from.file() != mv.from.file()) {
+            return mv.from.file().to_string();
+        }
+        if rivals.iter().all(|m| m.from.rank() != mv.from.rank()) {
+            return mv.from.rank().to_string();
+        }
+        mv.from.to_string()
+    }
+}
+
+
+// Letter of a piece in SAN, the same for both sides.
+pub(crate) fn piece_letter(piece: Piece) -> char {
+    match piece {
+        Piece::King(_) => 'K',
+        Piece::Queen(_) => 'Q',
+        Piece::Rook(_) => 'R',
+        Piece::Bishop(_) => 'B',
+        Piece::Knight(_) => 'N',
+        Piece::Pawn(_) => 'P',
+    }
+}
+
+
+fn piece_from_letter(letter: char, player: Player) -> Option<Piece> {
+    match letter {
+        'K' => Some(Piece::King(player)),
+        'Q' => Some(Piece::Queen(player)),
+        'R' => Some(Piece::Rook(player)),
+        'B' => Some(Piece::Bishop(player)),
+        'N' => Some(Piece::Knight(player)),
+        _ => None,
+    }
+}
@@ -0,0 +1,144 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::ChessError;
+
+
+// Column of the board, from a to h.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct File(u8);
+
+impl File {
+    pub const A: File = File(0);
+    pub const B: File = File(1);
+    pub const C: File = File(2);
+    pub const D: File = File(3);
+    pub const E: File = File(4);
+    pub const F: File = File(5);
+    pub const G: File = File(6);
+    pub const H: File = File(7);
+
+    pub fn new(index: usize) -> Option<File> {
+        if index < 8 { Some(File(index as u8)) } else { None }
+    }
+
+    // 0 for the a-file up to 7 for the h-file.
+    pub fn index(&self) -> usize {
+        self.0 as usize
+    }
+}
+
+impl fmt::Display for File {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", (b'a' + self.0) as char)
+    }
+}
+
+impl FromStr for File {
+    type Err = ChessError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.as_bytes() {
+            [letter @ b'a'..=b'h'] => Ok(File(letter - b'a')),
+            _ => Err(ChessError::InvalidSquare(s.to_string())),
+        }
+    }
+}
+
+// Row of the board, from 1 (White's side) to 8 (Black's side).
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Rank(u8);
+
+impl Rank {
+    pub const FIRST: Rank = Rank(0);
+    pub const SECOND: Rank = Rank(1);
+    pub const THIRD: Rank = Rank(2);
+    pub const FOURTH: Rank = Rank(3);
+    pub const FIFTH: Rank = Rank(4);
+    pub const SIXTH: Rank = Rank(5);
+    pub const SEVENTH: Rank = Rank(6);
+    pub const EIGHTH: Rank = Rank(7);
+
+    pub fn new(index: usize) -> Option<Rank> {
+        if index < 8 { Some(Rank(index as u8)) } else { None }
+    }
+
+    // 0 for the first rank up to 7 for the eighth, i.e. one less than the rank's name.
+    pub fn index(&self) -> usize {
+        self.0 as usize
+    }
+}
+
+impl fmt::Display for Rank {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", (b'1' + self.0) as char)
+    }
+}
+
+impl FromStr for Rank {
+    type Err = ChessError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.as_bytes() {
+            [digit @ b'1'..=b'8'] => Ok(Rank(digit - b'1')),
+            _ => Err(ChessError::InvalidSquare(s.to_string())),
+        }
+    }
+}
+
+// One of the 64 squares, numbered from a1 = 0 to h8 = 63 rank by rank.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Square(u8);
+
+impl Square {
+    pub fn new(file: File, rank: Rank) -> Square {
+        Square(rank.0 * 8 + file.0)
+    }
+
+    pub fn from_index(index: usize) -> Option<Square> {
+        if index < 64 { Some(Square(index as u8)) } else { None }
+    }
+
+    pub fn index(&self) -> usize {
+        self.0 as usize
+    }
+
+    pub fn file(&self) -> File {
+        File(self.0 % 8)
+    }
+
+    pub fn rank(&self) -> Rank {
+        Rank(self.0 / 8)
+    }
+
+    // Square reached by moving along files and ranks, if it stays on the board.
+    pub fn offset(&self, file_step: isize, rank_step: isize) -> Option<Square> {
+        let file = File::new(self.file().index().checked_add_signed(file_step)?)?;
+        let rank = Rank::new(self.rank().index().checked_add_signed(rank_step)?)?;
+        Some(Square::new(file, rank))
+    }
+
+    // Every square, a1 to h1 first and h8 last.
+    pub fn all() -> impl Iterator<Item = Square> {
+        (0..64).map(Square)
+    }
+}
+
+impl fmt::Display for Square {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}{}", self.file(), self.rank())
+    }
+}
+
+impl FromStr for Square {
+    type Err = ChessError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.len() != 2 || !s.is_char_boundary(1) {
+            return Err(ChessError::InvalidSquare(s.to_string()));
+        }
+        let file:File = s[..1].parse().map_err(|_| ChessError::InvalidSquare(s.to_string()))?;
+        let rank:Rank = s[1..].parse().map_err(|_| ChessError::InvalidSquare(s.to_string()))?;
+        Ok(Square::new(file, rank))
+    }
+}
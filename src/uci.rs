@@ -0,0 +1,67 @@
+use crate::{ChessError, File, Move, Piece, Player, Position, Rank, Square, parse_position, parse_promotion};
+
+
+// How castling is written in UCI.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum CastlingMode {
+    Standard,  // The king goes two squares, as in "e1g1"
+    Chess960,  // The king takes its own rook, as in "e1h1"
+}
+
+impl Move {
+    // Long algebraic notation of engines and GUIs: "e2e4", "e7e8q" or "e1g1" for castling.
+    pub fn to_uci(&self) -> String {
+        self.to_uci_with(CastlingMode::Standard)
+    }
+
+    pub fn to_uci_with(&self, castling_mode: CastlingMode) -> String {
+        let to:Square = match castling_mode {
+            CastlingMode::Chess960 if self.is_castle() => {
+                let rook_file:File = if self.to.file() == File::G { File::H } else { File::A };
+                Square::new(rook_file, self.to.rank())
+            },
+            _ => self.to,
+        };
+        let promotion:&str = match self.promotion {
+            Some(Piece::Queen(_)) => "q",
+            Some(Piece::Rook(_)) => "r",
+            Some(Piece::Bishop(_)) => "b",
+            Some(Piece::Knight(_)) => "n",
+            _ => "",
+        };
+        format!("{}{}{}", self.from, to, promotion)
+    }
+}
+
+impl Position {
+    // The legal move written in UCI. Castling is read both as the king going two squares and as the king taking its rook.
+    pub fn parse_uci(&self, uci: &str) -> Result<Move, ChessError> {
+        let invalid = || ChessError::InvalidNotation(uci.to_string());
+        if !uci.is_ascii() || (uci.len() != 4 && uci.len() != 5) {
+            return Err(invalid());
+        }
+        let from:Square = parse_position(&uci[0..2]).map_err(|_| invalid())?;
+        let mut to:Square = parse_position(&uci[2..4]).map_err(|_| invalid())?;
+
+        let player:Player = self.side_to_move();
+        let promotion:Option<Piece> = match uci.get(4..) {
+            Some("") | None => None,
+            Some(letter) => Some(parse_promotion(letter, player).map_err(|_| invalid())?),
+        };
+
+        // A king on e1 or e8 onto its own rook in the corner is the Chess960 way of writing castling.
+        let home_rank:Rank = if player == Player::White { Rank::FIRST } else { Rank::EIGHTH };
+        if from == Square::new(File::E, home_rank) && self.piece_at(from) == Some(Piece::King(player))
+            && matches!(to.file(), File::A | File::H) && to.rank() == home_rank && self.piece_at(to) == Some(Piece::Rook(player)) {
+            let king_file:File = if to.file() == File::H { File::G } else { File::C };
+            to = Square::new(king_file, home_rank);
+        }
+
+        // Unlike SAN, UCI always names the piece a pawn promotes to.
+        let mv:Move = self.validate_move(from, to, promotion)?;
+        if mv.promotion.is_some() && promotion.is_none() {
+            return Err(invalid());
+        }
+        Ok(mv)
+    }
+}
@@ -1,4 +1,4 @@
-use chessme::{ChessBoard, Player, parse_position};
+use chessme::{ChessBoard, parse_position};
 
 fn setup_test_game() -> ChessBoard { 
     ChessBoard::new()
@@ -20,10 +20,10 @@ fn test_game_checkmate_f3_qh4_1() {
     for (start_str, end_str) in positions {
         let start = parse_position(start_str).expect("Postion can't be reached");
         let end = parse_position(end_str).expect("Postion can't be reached");
-        board.move_piece(start, end).unwrap();
+        board.move_piece(start, end, None).unwrap();
     }
     
-    let result = board.result(Player::Black); // Checking for black's result after the moves
+    let result = board.result(); // Checking for black's result after the moves
     assert_eq!(result, "0-1"); // Black wins
 }
 
@@ -43,10 +43,10 @@ fn test_game_checkmate_f3_qh4_2() {
     for (start_str, end_str) in positions {
         let start = parse_position(start_str).expect("Postion can't be reached");
         let end = parse_position(end_str).expect("Postion can't be reached");
-        board.move_piece(start, end).unwrap();
+        board.move_piece(start, end, None).unwrap();
     }
     
-    let result = board.result(Player::Black); // Checking for black's result after the moves
+    let result = board.result(); // Checking for black's result after the moves
     assert_eq!(result, "0-1"); // Black wins
 }
 
@@ -66,10 +66,10 @@ fn test_game_checkmate_f4_qh4_1() {
     for (start_str, end_str) in positions {
         let start = parse_position(start_str).expect("Postion can't be reached");
         let end = parse_position(end_str).expect("Postion can't be reached");
-        let _ = board.move_piece(start, end);
+        let _ = board.move_piece(start, end, None);
     }
 
-    let result = board.result(Player::Black); // Checking for black's result after the moves
+    let result = board.result(); // Checking for black's result after the moves
     assert_eq!(result, "0-1"); // Black wins
 }
 
@@ -89,10 +89,10 @@ fn test_game_checkmate_f4_qh4_2() {
     for (start_str, end_str) in positions {
         let start = parse_position(start_str).expect("Postion can't be reached");
         let end = parse_position(end_str).expect("Postion can't be reached");
-        board.move_piece(start, end).unwrap();
+        board.move_piece(start, end, None).unwrap();
     }
     
-    let result = board.result(Player::Black); // Checking for black's result after the moves
+    let result = board.result(); // Checking for black's result after the moves
     assert_eq!(result, "0-1"); // Black wins
 }
 
@@ -112,10 +112,10 @@ fn test_game_checkmate_g4_qh4_1() {
     for (start_str, end_str) in positions {
         let start = parse_position(start_str).expect("Postion can't be reached");
         let end = parse_position(end_str).expect("Postion can't be reached");
-        board.move_piece(start, end).unwrap();
+        board.move_piece(start, end, None).unwrap();
     }
     
-    let result = board.result(Player::Black); // Checking for black's result after the moves
+    let result = board.result(); // Checking for black's result after the moves
     assert_eq!(result, "0-1"); // Black wins
 }
 
@@ -136,10 +136,10 @@ fn test_game_checkmate_g4_qh4_2() {
     for (start_str, end_str) in positions {
         let start = parse_position(start_str).expect("Postion can't be reached");
         let end = parse_position(end_str).expect("Postion can't be reached");
-        board.move_piece(start, end).unwrap();
+        board.move_piece(start, end, None).unwrap();
     }
     
-    let result = board.result(Player::Black); // Checking for black's result after the moves
+    let result = board.result(); // Checking for black's result after the moves
     assert_eq!(result, "0-1"); // Black wins
 }
 
@@ -159,10 +159,10 @@ fn test_game_checkmate_g4_qh4_3() {
     for (start_str, end_str) in positions {
         let start = parse_position(start_str).expect("Postion can't be reached");
         let end = parse_position(end_str).expect("Postion can't be reached");
-        board.move_piece(start, end).unwrap();
+        board.move_piece(start, end, None).unwrap();
     }
     
-    let result = board.result(Player::Black); // Checking for black's result after the moves
+    let result = board.result(); // Checking for black's result after the moves
     assert_eq!(result, "0-1"); // Black wins
 }
 
@@ -183,9 +183,9 @@ fn test_game_checkmate_g4_qh4_4() {
     for (start_str, end_str) in positions {
         let start = parse_position(start_str).expect("Postion can't be reached");
         let end = parse_position(end_str).expect("Postion can't be reached");
-        board.move_piece(start, end).unwrap();
+        board.move_piece(start, end, None).unwrap();
     }
     
-    let result = board.result(Player::Black); // Checking for black's result after the moves
+    let result = board.result(); // Checking for black's result after the moves
     assert_eq!(result, "0-1"); // Black wins
 }
\ No newline at end of file
@@ -1,4 +1,4 @@
-use chessme::{ChessBoard, Player, parse_position};
+use chessme::{ChessBoard, parse_position};
 
 
 fn setup_test_game() -> ChessBoard { 
@@ -22,9 +22,9 @@ fn test_game_checkmate_qh5_qxe5() {
     for (start_str, end_str) in positions {
         let start = parse_position(start_str).expect("Postion can't be reached");
         let end = parse_position(end_str).expect("Postion can't be reached");
-        board.move_piece(start, end).unwrap();
+        board.move_piece(start, end, None).unwrap();
     }
 
-    let result = board.result(Player::White); // Checking for white's result after the moves
+    let result = board.result(); // Checking for white's result after the moves
     assert_eq!(result, "1-0"); // White wins
 }
@@ -0,0 +1,96 @@
+mod common;
+
+use chessme::{Piece, Player, parse_position};
+use common::{setup_test_game, play};
+
+
+#[test]
+fn test_castling_king_side() {
+    let mut board = setup_test_game();
+
+    play(&mut board, &[
+        ("e2","e4"), // White plays e4
+        ("e7","e5"), // Black plays e5
+        ("g1","f3"), // White plays Nf3
+        ("b8","c6"), // Black plays Nc6
+        ("f1","c4"), // White plays Bc4
+        ("g8","f6"), // Black plays Nf6
+        ("e1","g1"), // White plays O-O
+    ]);
+
+    assert_eq!(board.position().piece_at(parse_position("g1").unwrap()), Some(Piece::King(Player::White)));
+    assert_eq!(board.position().piece_at(parse_position("f1").unwrap()), Some(Piece::Rook(Player::White)));
+    assert_eq!(board.position().piece_at(parse_position("h1").unwrap()), None);
+    assert!(!board.position().castling_rights().white_king_side);
+    assert!(!board.position().castling_rights().white_queen_side);
+    assert!(board.position().castling_rights().black_king_side);
+}
+
+#[test]
+fn test_castling_queen_side() {
+    let mut board = setup_test_game();
+
+    play(&mut board, &[
+        ("d2","d4"), // White plays d4
+        ("d7","d5"), // Black plays d5
+        ("b1","c3"), // White plays Nc3
+        ("b8","c6"), // Black plays Nc6
+        ("c1","f4"), // White plays Bf4
+        ("c8","f5"), // Black plays Bf5
+        ("d1","d2"), // White plays Qd2
+        ("d8","d7"), // Black plays Qd7
+        ("e1","c1"), // White plays O-O-O
+        ("e8","c8"), // Black plays O-O-O
+    ]);
+
+    assert_eq!(board.position().piece_at(parse_position("c1").unwrap()), Some(Piece::King(Player::White)));
+    assert_eq!(board.position().piece_at(parse_position("d1").unwrap()), Some(Piece::Rook(Player::White)));
+    assert_eq!(board.position().piece_at(parse_position("c8").unwrap()), Some(Piece::King(Player::Black)));
+    assert_eq!(board.position().piece_at(parse_position("d8").unwrap()), Some(Piece::Rook(Player::Black)));
+    assert_eq!(board.position().castling_rights(), chessme::CastlingRights::none());
+}
+
+#[test]
+fn test_castling_rejected_through_check() {
+    let mut board = setup_test_game();
+
+    play(&mut board, &[
+        ("e2","e4"), // White plays e4
+        ("e7","e5"), // Black plays e5
+        ("g1","f3"), // White plays Nf3
+        ("d7","d6"), // Black plays d6
+        ("f1","c4"), // White plays Bc4
+        ("c8","g4"), // Black plays Bg4
+        ("f3","g5"), // White plays Ng5
+        ("g4","d1"), // Black plays Bxd1
+        ("c4","f7"), // White plays Bxf7+
+        ("e8","e7"), // Black plays Ke7
+        ("g5","h3"), // White plays Nh3
+        ("d1","e2"), // Black plays Be2, covering f1
+    ]);
+
+    let king = parse_position("e1").unwrap();
+    assert!(board.check_move(king, parse_position("g1").unwrap(), None).is_err());
+}
+
+#[test]
+fn test_castling_rights_lost_after_rook_moves() {
+    let mut board = setup_test_game();
+
+    play(&mut board, &[
+        ("g1","f3"), // White plays Nf3
+        ("g8","f6"), // Black plays Nf6
+        ("g2","g3"), // White plays g3
+        ("g7","g6"), // Black plays g6
+        ("f1","g2"), // White plays Bg2
+        ("f8","g7"), // Black plays Bg7
+        ("h1","g1"), // White plays Rg1
+        ("h8","g8"), // Black plays Rg8
+        ("g1","h1"), // White plays Rh1
+        ("g8","h8"), // Black plays Rh8
+    ]);
+
+    let king = parse_position("e1").unwrap();
+    assert!(!board.position().castling_rights().white_king_side);
+    assert!(board.check_move(king, parse_position("g1").unwrap(), None).is_err());
+}
@@ -0,0 +1,64 @@
+mod common;
+
+use chessme::{ChessBoard, Piece, Player, parse_position};
+use common::{setup_test_game, play};
+
+
+fn play_white(board: &mut ChessBoard, start_str: &str, end_str: &str) {
+    let start = parse_position(start_str).unwrap();
+    let end = parse_position(end_str).unwrap();
+    assert!(board.check_move(start, end, None).is_ok());
+    board.move_piece(start, end, None).unwrap();
+}
+
+#[test]
+fn test_en_passant_capture() {
+    let mut board = setup_test_game();
+
+    play(&mut board, &[
+        ("e2","e4"), // White plays e4
+        ("a7","a6"), // Black plays a6
+        ("e4","e5"), // White plays e5
+        ("d7","d5"), // Black plays d5
+    ]);
+    assert_eq!(board.position().en_passant(), parse_position("d6").ok());
+
+    play_white(&mut board, "e5", "d6"); // White plays exd6 e.p.
+
+    assert_eq!(board.position().piece_at(parse_position("d6").unwrap()), Some(Piece::Pawn(Player::White)));
+    assert_eq!(board.position().piece_at(parse_position("d5").unwrap()), None);
+    assert_eq!(board.position().en_passant(), None);
+}
+
+#[test]
+fn test_en_passant_only_right_after_double_push() {
+    let mut board = setup_test_game();
+
+    play(&mut board, &[
+        ("e2","e4"), // White plays e4
+        ("d7","d5"), // Black plays d5
+        ("e4","e5"), // White plays e5
+        ("a7","a6"), // Black plays a6
+    ]);
+
+    let start = parse_position("e5").unwrap();
+    let end = parse_position("d6").unwrap();
+    assert!(board.check_move(start, end, None).is_err());
+}
+
+#[test]
+fn test_en_passant_from_a_file() {
+    let mut board = setup_test_game();
+
+    play(&mut board, &[
+        ("a2","a4"), // White plays a4
+        ("h7","h6"), // Black plays h6
+        ("a4","a5"), // White plays a5
+        ("b7","b5"), // Black plays b5
+    ]);
+
+    play_white(&mut board, "a5", "b6"); // White plays axb6 e.p.
+
+    assert_eq!(board.position().piece_at(parse_position("b6").unwrap()), Some(Piece::Pawn(Player::White)));
+    assert_eq!(board.position().piece_at(parse_position("b5").unwrap()), None);
+}
@@ -0,0 +1,64 @@
+mod common;
+
+use chessme::{ChessBoard, Piece, Player, parse_position};
+use common::{setup_test_game, play};
+
+
+// Runs the white h-pawn up to h7, with a black rook waiting on g8.
+fn setup_promotion() -> ChessBoard {
+    let mut board = setup_test_game();
+    play(&mut board, &[
+        ("h2","h4"), // White plays h4
+        ("g7","g5"), // Black plays g5
+        ("h4","g5"), // White plays hxg5
+        ("h7","h6"), // Black plays h6
+        ("g5","h6"), // White plays gxh6
+        ("g8","f6"), // Black plays Nf6
+        ("h6","h7"), // White plays h7
+        ("h8","g8"), // Black plays Rg8
+    ]);
+    board
+}
+
+#[test]
+fn test_promotion_to_knight() {
+    let mut board = setup_promotion();
+
+    let start = parse_position("h7").unwrap();
+    let end = parse_position("g8").unwrap();
+    let knight = Some(Piece::Knight(Player::White));
+    assert!(board.check_move(start, end, knight).is_ok());
+    board.move_piece(start, end, knight).unwrap();
+
+    assert_eq!(board.position().piece_at(parse_position("g8").unwrap()), Some(Piece::Knight(Player::White)));
+    assert!(board.moves_history().back().unwrap().contains("=N"));
+}
+
+#[test]
+fn test_promotion_defaults_to_queen() {
+    let mut board = setup_promotion();
+
+    let start = parse_position("h7").unwrap();
+    let end = parse_position("h8").unwrap();
+    assert!(board.check_move(start, end, None).is_ok());
+    board.move_piece(start, end, None).unwrap();
+
+    assert_eq!(board.position().piece_at(parse_position("h8").unwrap()), Some(Piece::Queen(Player::White)));
+    assert!(board.moves_history().back().unwrap().contains("=Q"));
+    assert!(board.write_to_pgn("Player 1", "Player 2", None).contains("=Q"));
+}
+
+#[test]
+fn test_promotion_rejects_invalid_piece() {
+    let board = setup_promotion();
+
+    let start = parse_position("h7").unwrap();
+    let end = parse_position("h8").unwrap();
+    assert!(board.check_move(start, end, Some(Piece::King(Player::White))).is_err());
+    assert!(board.check_move(start, end, Some(Piece::Queen(Player::Black))).is_err());
+
+    // Promotion is only meaningful for a pawn reaching the last rank.
+    let start = parse_position("a2").unwrap();
+    let end = parse_position("a3").unwrap();
+    assert!(board.check_move(start, end, Some(Piece::Queen(Player::White))).is_err());
+}
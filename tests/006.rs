@@ -0,0 +1,57 @@
+mod common;
+
+use chessme::{ChessBoard, parse_position};
+use common::{setup_test_game, play};
+
+
+fn is_valid(board: &ChessBoard, start_str: &str, end_str: &str) -> bool {
+    let start = parse_position(start_str).expect("Position can't be reached");
+    let end = parse_position(end_str).expect("Position can't be reached");
+    board.position().is_valid_move(start, end)
+}
+
+#[test]
+fn test_check_must_be_answered() {
+    let mut board = setup_test_game();
+
+    play(&mut board, &[
+        ("e2","e4"), // White plays e4
+        ("f7","f6"), // Black plays f6
+        ("d1","h5"), // White plays Qh5+
+    ]);
+
+    assert!(!is_valid(&board, "a7", "a6")); // Ignores the check
+    assert!(is_valid(&board, "g7", "g6")); // Blocks the check
+}
+
+#[test]
+fn test_pinned_piece_cannot_move() {
+    let mut board = setup_test_game();
+
+    play(&mut board, &[
+        ("e2","e4"), // White plays e4
+        ("e7","e5"), // Black plays e5
+        ("g1","f3"), // White plays Nf3
+        ("b8","c6"), // Black plays Nc6
+        ("f1","b5"), // White plays Bb5
+        ("d7","d6"), // Black plays d6, pinning the knight
+        ("c2","c3"), // White plays c3
+    ]);
+
+    assert!(!is_valid(&board, "c6", "d4"));
+    assert!(!is_valid(&board, "c6", "e7"));
+    assert!(is_valid(&board, "c8", "d7"));
+}
+
+#[test]
+fn test_king_cannot_step_next_to_enemy_king() {
+    let mut board = setup_test_game();
+
+    // Bring both kings into the centre without caring about the rules.
+    board.move_piece(parse_position("e1").unwrap(), parse_position("e4").unwrap(), None).unwrap();
+    board.move_piece(parse_position("e8").unwrap(), parse_position("e6").unwrap(), None).unwrap();
+
+    assert!(!is_valid(&board, "e4", "e5"));
+    assert!(!is_valid(&board, "e4", "f5"));
+    assert!(is_valid(&board, "e4", "d4"));
+}
@@ -0,0 +1,88 @@
+mod common;
+
+use chessme::{Move, MoveKind, Piece, Player, parse_position};
+use common::{setup_test_game, play};
+
+
+#[test]
+fn test_legal_moves_from_start() {
+    let mut board = setup_test_game();
+
+    assert_eq!(board.position().legal_moves().len(), 20);
+    assert!(board.position().legal_captures().is_empty());
+
+    // Black has the same twenty answers.
+    play(&mut board, &[("e2","e4")]);
+    assert_eq!(board.position().legal_moves().len(), 20);
+}
+
+#[test]
+fn test_legal_captures() {
+    let mut board = setup_test_game();
+
+    play(&mut board, &[
+        ("e2","e4"), // White plays e4
+        ("d7","d5"), // Black plays d5
+    ]);
+
+    let captures = board.position().legal_captures();
+    assert_eq!(captures, vec![Move {
+        from: parse_position("e4").unwrap(),
+        to: parse_position("d5").unwrap(),
+        piece: Piece::Pawn(Player::White),
+        captured: Some(Piece::Pawn(Player::Black)),
+        promotion: None,
+        kind: MoveKind::Normal,
+    }]);
+}
+
+#[test]
+fn test_pseudo_legal_moves_include_pinned_piece() {
+    let mut board = setup_test_game();
+
+    play(&mut board, &[
+        ("e2","e4"), // White plays e4
+        ("e7","e5"), // Black plays e5
+        ("g1","f3"), // White plays Nf3
+        ("b8","c6"), // Black plays Nc6
+        ("f1","b5"), // White plays Bb5
+        ("d7","d6"), // Black plays d6, pinning the knight
+        ("c2","c3"), // White plays c3
+    ]);
+
+    let knight = parse_position("c6").unwrap();
+    let pseudo_legal = board.position().pseudo_legal_moves();
+    let legal = board.position().legal_moves();
+    assert!(pseudo_legal.iter().any(|m| m.from == knight));
+    assert!(!legal.iter().any(|m| m.from == knight));
+    assert!(legal.len() < pseudo_legal.len());
+}
+
+#[test]
+fn test_promotions_are_generated_for_each_piece() {
+    let mut board = setup_test_game();
+
+    play(&mut board, &[
+        ("h2","h4"), // White plays h4
+        ("g7","g5"), // Black plays g5
+        ("h4","g5"), // White plays hxg5
+        ("h7","h6"), // Black plays h6
+        ("g5","h6"), // White plays gxh6
+        ("g8","f6"), // Black plays Nf6
+        ("h6","h7"), // White plays h7
+        ("h8","g8"), // Black plays Rg8
+    ]);
+
+    let pawn = parse_position("h7").unwrap();
+    let promotions: Vec<Option<Piece>> = board.position().legal_moves()
+        .iter()
+        .filter(|m| m.from == pawn && m.to == parse_position("h8").unwrap())
+        .map(|m| m.promotion)
+        .collect();
+    assert_eq!(promotions, vec![
+        Some(Piece::Queen(Player::White)),
+        Some(Piece::Rook(Player::White)),
+        Some(Piece::Bishop(Player::White)),
+        Some(Piece::Knight(Player::White)),
+    ]);
+}
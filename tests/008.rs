@@ -0,0 +1,76 @@
+mod common;
+
+use chessme::{GameOutcome, Player, parse_position};
+use common::setup_test_game;
+
+
+#[test]
+fn test_outcome_checkmate() {
+    // Simulate the moves for the sequence: f3 e5 g4 Qh4#
+    
+    let mut board = setup_test_game();
+
+    let positions = vec![
+        ("f2","f3"), // White plays f3 
+        ("e7","e5"), // Black plays e5
+        ("g2","g4"), // White plays g4
+        ("d8","h4"), // Black plays Qh4#
+    ];
+    
+    for (start_str, end_str) in positions {
+        let start = parse_position(start_str).expect("Position can't be reached");
+        let end = parse_position(end_str).expect("Position can't be reached");
+        board.move_piece(start, end, None).unwrap();
+    }
+
+    let outcome = board.outcome();
+    assert_eq!(outcome, Some(GameOutcome::Checkmate { winner: Player::Black }));
+    assert_eq!(outcome.unwrap().winner(), Some(Player::Black));
+    assert!(board.write_to_pgn("Player 1", "Player 2", outcome).contains("[Result \"0-1\"]"));
+}
+
+#[test]
+fn test_outcome_stalemate() {
+    // Simulate the shortest known stalemate:
+    // e3 a5 Qh5 Ra6 Qxa5 h5 h4 Rah6 Qxc7 f6 Qxd7+ Kf7 Qxb7 Qd3 Qxb8 Qh7 Qxc8 Kg6 Qe6
+    
+    let mut board = setup_test_game();
+
+    let positions = vec![
+        ("e2","e3"), ("a7","a5"),
+        ("d1","h5"), ("a8","a6"),
+        ("h5","a5"), ("h7","h5"),
+        ("h2","h4"), ("a6","h6"),
+        ("a5","c7"), ("f7","f6"),
+        ("c7","d7"), ("e8","f7"),
+        ("d7","b7"), ("d8","d3"),
+        ("b7","b8"), ("d3","h7"),
+        ("b8","c8"), ("f7","g6"),
+        ("c8","e6"),
+    ];
+    
+    for (start_str, end_str) in positions {
+        let start = parse_position(start_str).expect("Position can't be reached");
+        let end = parse_position(end_str).expect("Position can't be reached");
+        board.move_piece(start, end, None).unwrap();
+    }
+
+    assert_eq!(board.outcome(), Some(GameOutcome::Stalemate));
+    assert_eq!(board.result(), "1/2-1/2");
+}
+
+#[test]
+fn test_outcome_ongoing() {
+    let board = setup_test_game();
+
+    assert_eq!(board.outcome(), None);
+    assert_eq!(board.result(), "*");
+}
+
+#[test]
+fn test_outcome_result_strings() {
+    assert_eq!(GameOutcome::Resignation { winner: Player::White }.result(), "1-0");
+    assert_eq!(GameOutcome::Timeout { winner: Player::Black }.result(), "0-1");
+    assert_eq!(GameOutcome::Agreement.result(), "1/2-1/2");
+    assert_eq!(GameOutcome::Agreement.winner(), None);
+}
@@ -0,0 +1,79 @@
+mod common;
+
+use chessme::GameOutcome;
+use common::{setup_test_game, play};
+
+
+// Squares Black's king's knight goes round while White's knight hops between g1 and f3.
+const BLACK_KNIGHT_LOOP: [&str; 20] = [
+    "f6", "h5", "f4", "g6", "h4", "f5", "h6", "g4", "e5", "c6",
+    "d4", "e6", "g5", "e4", "c5", "a4", "b6", "c4", "e3", "d5",
+];
+
+// Knight moves only, so no capture or pawn move, and the loop is long enough that
+// no position comes back a fifth time within seventy-five moves.
+fn wandering_moves(half_moves: usize) -> Vec<(&'static str, &'static str)> {
+    let mut positions = vec![("g1","f3"), ("g8","f6")];
+    let mut move_number = 1;
+    while positions.len() < half_moves {
+        let white_move = if move_number % 2 == 1 { ("f3","g1") } else { ("g1","f3") };
+        let black_move = (
+            BLACK_KNIGHT_LOOP[(move_number - 1) % BLACK_KNIGHT_LOOP.len()],
+            BLACK_KNIGHT_LOOP[move_number % BLACK_KNIGHT_LOOP.len()],
+        );
+        positions.push(white_move);
+        positions.push(black_move);
+        move_number += 1;
+    }
+    positions.truncate(half_moves);
+    positions
+}
+
+#[test]
+fn test_clocks_follow_the_moves() {
+    let mut board = setup_test_game();
+    assert_eq!((board.position().halfmove_clock(), board.position().fullmove_number()), (0, 1));
+
+    play(&mut board, &[("g1","f3")]); // White plays Nf3
+    assert_eq!((board.position().halfmove_clock(), board.position().fullmove_number()), (1, 1));
+
+    play(&mut board, &[("b8","c6")]); // Black plays Nc6
+    assert_eq!((board.position().halfmove_clock(), board.position().fullmove_number()), (2, 2));
+
+    play(&mut board, &[("e2","e4")]); // White plays e4
+    assert_eq!((board.position().halfmove_clock(), board.position().fullmove_number()), (0, 2));
+
+    play(&mut board, &[("c6","d4"), ("f3","d4")]); // Black plays Nd4, White plays Nxd4
+    assert_eq!((board.position().halfmove_clock(), board.position().fullmove_number()), (0, 3));
+}
+
+#[test]
+fn test_fifty_move_rule_can_be_claimed() {
+    let mut board = setup_test_game();
+
+    let positions = wandering_moves(100);
+    play(&mut board, &positions[..99]);
+    assert!(!board.position().can_claim_fifty_moves());
+
+    play(&mut board, &positions[99..]);
+    assert_eq!(board.position().halfmove_clock(), 100);
+    assert!(board.position().can_claim_fifty_moves());
+    assert_eq!(board.claim_draw(), Some(GameOutcome::FiftyMoves));
+
+    // The game only ends if a player claims it.
+    assert_eq!(board.outcome(), None);
+}
+
+#[test]
+fn test_seventy_five_move_rule_ends_the_game() {
+    let mut board = setup_test_game();
+
+    let positions = wandering_moves(150);
+    play(&mut board, &positions[..149]);
+    assert_eq!(board.outcome(), None);
+
+    play(&mut board, &positions[149..]);
+    assert_eq!(board.position().halfmove_clock(), 150);
+    assert_eq!(board.outcome(), Some(GameOutcome::SeventyFiveMoves));
+    assert_eq!(board.result(), "1/2-1/2");
+}
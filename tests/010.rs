@@ -0,0 +1,81 @@
+mod common;
+
+use chessme::{ChessBoard, GameOutcome};
+use common::{setup_test_game, play};
+
+
+// Both sides bring their king's knight out and back to the starting position.
+const KNIGHTS_OUT_AND_BACK: [(&str, &str); 4] = [("g1","f3"), ("g8","f6"), ("f3","g1"), ("f6","g8")];
+
+#[test]
+fn test_threefold_repetition_can_be_claimed() {
+    let mut board = setup_test_game();
+
+    play(&mut board, &KNIGHTS_OUT_AND_BACK);
+    assert_eq!(board.repetition_count(), 2);
+    assert!(!board.can_claim_threefold_repetition());
+
+    play(&mut board, &KNIGHTS_OUT_AND_BACK);
+    assert_eq!(board.repetition_count(), 3);
+    assert!(board.can_claim_threefold_repetition());
+    assert_eq!(board.claim_draw(), Some(GameOutcome::ThreefoldRepetition));
+
+    // The game only ends if a player claims it.
+    assert_eq!(board.outcome(), None);
+}
+
+#[test]
+fn test_fivefold_repetition_ends_the_game() {
+    let mut board = setup_test_game();
+
+    for _ in 0..3 {
+        play(&mut board, &KNIGHTS_OUT_AND_BACK);
+    }
+    assert_eq!(board.outcome(), None);
+
+    play(&mut board, &KNIGHTS_OUT_AND_BACK);
+    assert_eq!(board.repetition_count(), 5);
+    assert_eq!(board.outcome(), Some(GameOutcome::FivefoldRepetition));
+    assert_eq!(board.result(), "1/2-1/2");
+}
+
+#[test]
+fn test_lost_castling_rights_change_the_position() {
+    let mut board = setup_test_game();
+
+    // Same placement as the start, but the kings have moved and can no longer castle.
+    play(&mut board, &[("e2","e4"), ("e7","e5"), ("e1","e2"), ("e8","e7"), ("e2","e1"), ("e7","e8")]);
+    play(&mut board, &[("e1","e2"), ("e8","e7"), ("e2","e1"), ("e7","e8")]);
+    assert_eq!(board.repetition_count(), 2);
+    assert!(board.position().position_key().ends_with(" w - -"));
+}
+
+#[test]
+fn test_en_passant_square_counts_only_when_capturable() {
+    let mut board = setup_test_game();
+
+    // After e4 nothing can take en passant, so the key shows no en passant square.
+    play(&mut board, &[("e2","e4")]);
+    assert!(board.position().position_key().ends_with(" b KQkq -"));
+
+    play(&mut board, &[("d7","d5"), ("e4","e5"), ("f7","f5")]);
+    assert!(board.position().position_key().ends_with(" w KQkq f6"));
+}
+
+#[test]
+fn test_pinned_en_passant_does_not_count_for_repetitions() {
+    let mut board = ChessBoard::from_fen("8/2p5/8/KP5r/8/8/8/7k b - - 0 1").unwrap();
+
+    // b5xc6 would leave the white king in check from the rook, so c6 is no en passant square.
+    play(&mut board, &[("c7","c5")]);
+    let key:u64 = board.position().repetition_key();
+    assert_ne!(key, board.position().zobrist_key());
+
+    // The king shuffle comes back twice to the position after c5.
+    let king_shuffle = [("a5","a6"), ("h1","g1"), ("a6","a5"), ("g1","h1")];
+    play(&mut board, &king_shuffle);
+    play(&mut board, &king_shuffle);
+    assert_eq!(board.position().repetition_key(), key);
+    assert_eq!(board.repetition_count(), 3);
+    assert_eq!(board.claim_draw(), Some(GameOutcome::ThreefoldRepetition));
+}
@@ -0,0 +1,102 @@
+use chessme::{ChessBoard, GameOutcome, Piece, Player, Position, Square, parse_position};
+
+
+// Empty board with only the given pieces on it, White to move.
+fn setup_position(pieces: &[(&str, Piece)]) -> ChessBoard { 
+    let pieces: Vec<(Square, Piece)> = pieces
+        .iter()
+        .map(|(square_str, piece)| (parse_position(square_str).expect("Position can't be reached"), *piece))
+        .collect();
+    ChessBoard::from_position(Position::from_pieces(&pieces, Player::White))
+}
+
+#[test]
+fn test_bare_kings() {
+    let board = setup_position(&[
+        ("e1", Piece::King(Player::White)),
+        ("e8", Piece::King(Player::Black)),
+    ]);
+
+    assert!(board.position().is_insufficient_material());
+    assert_eq!(board.outcome(), Some(GameOutcome::InsufficientMaterial));
+    assert_eq!(board.result(), "1/2-1/2");
+}
+
+#[test]
+fn test_king_and_minor_piece() {
+    let knight = setup_position(&[
+        ("e1", Piece::King(Player::White)),
+        ("g1", Piece::Knight(Player::White)),
+        ("e8", Piece::King(Player::Black)),
+    ]);
+    assert!(knight.position().is_insufficient_material());
+
+    let bishop = setup_position(&[
+        ("e1", Piece::King(Player::White)),
+        ("e8", Piece::King(Player::Black)),
+        ("c8", Piece::Bishop(Player::Black)),
+    ]);
+    assert!(bishop.position().is_insufficient_material());
+}
+
+#[test]
+fn test_bishops_on_same_colored_squares() {
+    let same_color = setup_position(&[
+        ("e1", Piece::King(Player::White)),
+        ("c1", Piece::Bishop(Player::White)),
+        ("e8", Piece::King(Player::Black)),
+        ("f8", Piece::Bishop(Player::Black)),
+    ]);
+    assert!(same_color.position().is_insufficient_material());
+
+    let opposite_colors = setup_position(&[
+        ("e1", Piece::King(Player::White)),
+        ("c1", Piece::Bishop(Player::White)),
+        ("e8", Piece::King(Player::Black)),
+        ("c8", Piece::Bishop(Player::Black)),
+    ]);
+    assert!(!opposite_colors.position().is_insufficient_material());
+}
+
+#[test]
+fn test_mating_material_is_sufficient() {
+    let rook = setup_position(&[
+        ("e1", Piece::King(Player::White)),
+        ("a1", Piece::Rook(Player::White)),
+        ("e8", Piece::King(Player::Black)),
+    ]);
+    assert!(!rook.position().is_insufficient_material());
+    assert_eq!(rook.outcome(), None);
+
+    let pawn = setup_position(&[
+        ("e1", Piece::King(Player::White)),
+        ("e8", Piece::King(Player::Black)),
+        ("a7", Piece::Pawn(Player::Black)),
+    ]);
+    assert!(!pawn.position().is_insufficient_material());
+
+    let knight_and_bishop = setup_position(&[
+        ("e1", Piece::King(Player::White)),
+        ("b1", Piece::Knight(Player::White)),
+        ("c1", Piece::Bishop(Player::White)),
+        ("e8", Piece::King(Player::Black)),
+    ]);
+    assert!(!knight_and_bishop.position().is_insufficient_material());
+}
+
+#[test]
+fn test_capturing_the_last_piece_ends_the_game() {
+    let mut board = setup_position(&[
+        ("e1", Piece::King(Player::White)),
+        ("e8", Piece::King(Player::Black)),
+        ("d7", Piece::Rook(Player::Black)),
+        ("b6", Piece::Knight(Player::White)),
+    ]);
+    assert_eq!(board.outcome(), None);
+
+    let start = parse_position("b6").unwrap();
+    let end = parse_position("d7").unwrap();
+    assert!(board.check_move(start, end, None).is_ok());
+    board.move_piece(start, end, None).unwrap();
+    assert_eq!(board.outcome(), Some(GameOutcome::InsufficientMaterial));
+}
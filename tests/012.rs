@@ -0,0 +1,142 @@
+mod common;
+
+use chessme::{ChessBoard, Move, MoveKind, Piece, Player, Position, parse_position};
+use common::{setup_test_game, play};
+
+
+fn find_move(position: &Position, start_str: &str, end_str: &str) -> Move {
+    let start = parse_position(start_str).expect("Position can't be reached");
+    let end = parse_position(end_str).expect("Position can't be reached");
+    *position.legal_moves().iter().find(|m| m.from == start && m.to == end).expect("Move should be legal")
+}
+
+#[test]
+fn test_make_unmake_restores_every_move() {
+    let mut board = setup_test_game();
+
+    // Castling and en passant are both available to White here.
+    play(&mut board, &[
+        ("e2","e4"), ("g8","f6"),
+        ("e4","e5"), ("a7","a6"),
+        ("g1","f3"), ("b7","b6"),
+        ("f1","c4"), ("d7","d5"),
+    ]);
+    assert!(board.position().legal_moves().iter().any(|m| m.to == parse_position("g1").unwrap()));
+
+    // Everything make/unmake has to put back is part of the position.
+    let before = *board.position();
+    let mut position = before;
+    for mv in before.legal_moves() {
+        let undo = position.make_move(mv);
+        assert_ne!(position, before);
+        position.unmake_move(mv, undo);
+        assert_eq!(position, before);
+    }
+}
+
+#[test]
+fn test_make_unmake_en_passant_and_castling() {
+    let mut board = setup_test_game();
+
+    play(&mut board, &[
+        ("e2","e4"), ("g8","f6"),
+        ("e4","e5"), ("a7","a6"),
+        ("g1","f3"), ("b7","b6"),
+        ("f1","c4"), ("d7","d5"),
+    ]);
+    let before = *board.position();
+    let mut position = before;
+
+    let en_passant = find_move(&position, "e5", "d6");
+    assert!(en_passant.is_en_passant());
+    assert_eq!(en_passant.captured, Some(Piece::Pawn(Player::Black)));
+    let undo = position.make_move(en_passant);
+    assert_eq!(position.piece_at(parse_position("d5").unwrap()), None);
+    position.unmake_move(en_passant, undo);
+    assert_eq!(position.piece_at(parse_position("d5").unwrap()), Some(Piece::Pawn(Player::Black)));
+
+    let castling = find_move(&position, "e1", "g1");
+    assert!(castling.is_castle());
+    let undo = position.make_move(castling);
+    assert_eq!(position.piece_at(parse_position("f1").unwrap()), Some(Piece::Rook(Player::White)));
+    position.unmake_move(castling, undo);
+    assert_eq!(position.piece_at(parse_position("h1").unwrap()), Some(Piece::Rook(Player::White)));
+
+    assert_eq!(position, before);
+}
+
+#[test]
+fn test_only_the_king_on_its_home_square_castles() {
+    // A king going two squares anywhere else is an ordinary move, the rook stays where it is.
+    let mut board = ChessBoard::from_fen("4k3/8/8/8/4K2R/8/8/8 w - - 0 1").unwrap();
+    let mv = board.position().build_move(parse_position("e4").unwrap(), parse_position("g4").unwrap(), None).unwrap();
+    assert_eq!(mv.kind, MoveKind::Normal);
+
+    board.move_piece(mv.from, mv.to, None).unwrap();
+    assert_eq!(board.position().write_to_fen(), "4k3/8/8/8/6KR/8/8/8 b - - 1 1");
+    assert_eq!(board.moves_history()[0], "Kg4");
+
+    // Nor does a king from e1 without its rook in the corner.
+    let position = Position::from_fen("4k3/8/8/8/8/8/8/4K1R1 w - - 0 1").unwrap();
+    let mv = position.build_move(parse_position("e1").unwrap(), parse_position("g1").unwrap(), None).unwrap();
+    assert_eq!(mv.kind, MoveKind::Normal);
+}
+
+#[test]
+fn test_make_unmake_promotion_with_capture() {
+    let mut board = setup_test_game();
+
+    play(&mut board, &[
+        ("h2","h4"), ("g7","g5"),
+        ("h4","g5"), ("h7","h6"),
+        ("g5","h6"), ("g8","f6"),
+        ("h6","h7"), ("h8","g8"),
+    ]);
+    let before = *board.position();
+    let mut position = before;
+
+    let promotion = Move {
+        from: parse_position("h7").unwrap(),
+        to: parse_position("g8").unwrap(),
+        piece: Piece::Pawn(Player::White),
+        captured: Some(Piece::Rook(Player::Black)),
+        promotion: Some(Piece::Knight(Player::White)),
+        kind: MoveKind::Normal,
+    };
+    assert!(position.legal_moves().contains(&promotion));
+    let undo = position.make_move(promotion);
+    assert_eq!(position.piece_at(parse_position("g8").unwrap()), Some(Piece::Knight(Player::White)));
+    position.unmake_move(promotion, undo);
+
+    assert_eq!(position.piece_at(parse_position("h7").unwrap()), Some(Piece::Pawn(Player::White)));
+    assert_eq!(position.piece_at(parse_position("g8").unwrap()), Some(Piece::Rook(Player::Black)));
+    assert_eq!(position, before);
+}
+
+#[test]
+fn test_undo_and_redo() {
+    let mut board = setup_test_game();
+
+    play(&mut board, &[("e2","e4"), ("e7","e5"), ("g1","f3")]);
+    let after_three_moves = *board.position();
+    assert_eq!(board.moves_history().len(), 3);
+
+    let last = board.undo().unwrap();
+    assert_eq!(last.from, parse_position("g1").unwrap());
+    board.undo().unwrap();
+    assert_eq!(board.moves_history().len(), 1);
+    assert_eq!(board.position_history().len(), 2);
+    assert_eq!(board.position().piece_at(parse_position("e7").unwrap()), Some(Piece::Pawn(Player::Black)));
+
+    board.redo().unwrap();
+    board.redo().unwrap();
+    assert_eq!(board.redo(), None);
+    assert_eq!(*board.position(), after_three_moves);
+    assert_eq!(board.moves_history().len(), 3);
+
+    // Undoing everything gets back to the start, and a new move forgets what was undone.
+    while board.undo().is_some() {}
+    assert_eq!(*board.position(), Position::new());
+    play(&mut board, &[("d2","d4")]);
+    assert_eq!(board.redo(), None);
+}
@@ -0,0 +1,68 @@
+mod common;
+
+use chessme::{CastlingRights, ChessBoard, GameOutcome, Piece, Player, Position, parse_position};
+use common::{setup_test_game, play};
+
+
+#[test]
+fn test_initial_position() {
+    let position = Position::new();
+
+    assert_eq!(position.side_to_move(), Player::White);
+    assert_eq!(position.castling_rights(), CastlingRights::all());
+    assert_eq!(position.en_passant(), None);
+    assert_eq!((position.halfmove_clock(), position.fullmove_number()), (0, 1));
+    assert_eq!(position.piece_at(parse_position("e1").unwrap()), Some(Piece::King(Player::White)));
+    assert_eq!(position.piece_at(parse_position("d8").unwrap()), Some(Piece::Queen(Player::Black)));
+    assert_eq!(position.piece_at(parse_position("e4").unwrap()), None);
+}
+
+#[test]
+fn test_side_to_move_follows_the_moves() {
+    let mut board = setup_test_game();
+
+    play(&mut board, &[
+        ("e2","e4"), // White plays e4
+    ]);
+    assert_eq!(board.position().side_to_move(), Player::Black);
+    assert_eq!(board.position().en_passant(), parse_position("e3").ok());
+    assert_eq!(board.position().write_to_fen(), "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1");
+
+    play(&mut board, &[
+        ("e7","e5"), // Black plays e5
+    ]);
+    assert_eq!(board.position().side_to_move(), Player::White);
+    assert_eq!(board.position().fullmove_number(), 2);
+
+    board.undo().unwrap();
+    assert_eq!(board.position().side_to_move(), Player::Black);
+}
+
+#[test]
+fn test_moves_of_the_side_not_to_move_are_rejected() {
+    let board = setup_test_game();
+
+    let start = parse_position("e7").unwrap();
+    let end = parse_position("e5").unwrap();
+    assert!(!board.position().is_valid_move(start, end));
+    assert!(board.check_move(start, end, None).is_err());
+}
+
+#[test]
+fn test_position_from_pieces() {
+    // Black is checkmated in the corner.
+    let position = Position::from_pieces(&[
+        (parse_position("h8").unwrap(), Piece::King(Player::Black)),
+        (parse_position("g7").unwrap(), Piece::Queen(Player::White)),
+        (parse_position("f6").unwrap(), Piece::King(Player::White)),
+    ], Player::Black);
+
+    assert_eq!(position.castling_rights(), CastlingRights::none());
+    assert!(position.is_check());
+    assert!(position.is_checkmate());
+    assert!(!position.is_stalemate());
+
+    let board = ChessBoard::from_position(position);
+    assert_eq!(board.outcome(), Some(GameOutcome::Checkmate { winner: Player::White }));
+    assert_eq!(board.result(), "1-0");
+}
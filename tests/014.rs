@@ -0,0 +1,95 @@
+mod common;
+
+use chessme::{ChessBoard, Move, MoveKind, Piece, Player, parse_position};
+use common::{setup_test_game, play};
+
+
+fn find_move(board: &ChessBoard, start_str: &str, end_str: &str) -> Move {
+    let start = parse_position(start_str).expect("Position can't be reached");
+    let end = parse_position(end_str).expect("Position can't be reached");
+    *board.position().legal_moves().iter().find(|m| m.from == start && m.to == end).expect("Move should be legal")
+}
+
+#[test]
+fn test_generated_moves_carry_piece_and_kind() {
+    let board = setup_test_game();
+
+    let double_push = find_move(&board, "e2", "e4");
+    assert_eq!(double_push.piece, Piece::Pawn(Player::White));
+    assert_eq!(double_push.kind, MoveKind::DoublePush);
+    assert!(!double_push.is_capture());
+
+    let knight = find_move(&board, "g1", "f3");
+    assert_eq!(knight.piece, Piece::Knight(Player::White));
+    assert_eq!(knight.kind, MoveKind::Normal);
+}
+
+#[test]
+fn test_en_passant_and_castle_flags() {
+    let mut board = setup_test_game();
+
+    play(&mut board, &[
+        ("e2","e4"), // White plays e4
+        ("a7","a6"), // Black plays a6
+        ("e4","e5"), // White plays e5
+        ("d7","d5"), // Black plays d5
+        ("g1","f3"), // White plays Nf3
+        ("a6","a5"), // Black plays a5
+        ("f1","c4"), // White plays Bc4
+        ("b7","b6"), // Black plays b6
+    ]);
+
+    let castle = find_move(&board, "e1", "g1");
+    assert!(castle.is_castle());
+    assert_eq!(castle.piece, Piece::King(Player::White));
+
+    play(&mut board, &[
+        ("a2","a3"), // White plays a3
+        ("f7","f5"), // Black plays f5
+    ]);
+    let en_passant = find_move(&board, "e5", "f6");
+    assert!(en_passant.is_en_passant());
+    assert_eq!(en_passant.captured, Some(Piece::Pawn(Player::Black)));
+}
+
+#[test]
+fn test_played_moves_are_kept_with_their_details() {
+    let mut board = setup_test_game();
+
+    play(&mut board, &[
+        ("e2","e4"), // White plays e4
+        ("d7","d5"), // Black plays d5
+        ("e4","d5"), // White plays exd5
+    ]);
+
+    let moves = board.moves();
+    assert_eq!(moves.len(), 3);
+    assert!(moves[1].is_double_push());
+    assert_eq!(moves[2].captured, Some(Piece::Pawn(Player::Black)));
+    assert_eq!(moves[2].piece, Piece::Pawn(Player::White));
+
+    board.undo().unwrap();
+    assert_eq!(board.moves().len(), 2);
+}
+
+#[test]
+fn test_build_move_defaults_promotion_to_queen() {
+    let mut board = setup_test_game();
+
+    play(&mut board, &[
+        ("h2","h4"), // White plays h4
+        ("g7","g5"), // Black plays g5
+        ("h4","g5"), // White plays hxg5
+        ("h7","h6"), // Black plays h6
+        ("g5","h6"), // White plays gxh6
+        ("g8","f6"), // Black plays Nf6
+        ("h6","h7"), // White plays h7
+        ("h8","g8"), // Black plays Rg8
+    ]);
+
+    let mv = board.position().build_move(parse_position("h7").unwrap(), parse_position("g8").unwrap(), None).unwrap();
+    assert_eq!(mv.promotion, Some(Piece::Queen(Player::White)));
+    assert_eq!(mv.captured, Some(Piece::Rook(Player::Black)));
+    assert!(board.position().legal_moves().contains(&mv));
+    assert_eq!(board.position().build_move(parse_position("e4").unwrap(), parse_position("e5").unwrap(), None), None);
+}
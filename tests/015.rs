@@ -0,0 +1,54 @@
+mod common;
+
+use chessme::{ChessError, File, Piece, Player, Rank, Square, parse_position};
+use common::{setup_test_game, play};
+
+
+#[test]
+fn test_square_names_round_trip() {
+    let e4: Square = "e4".parse().unwrap();
+    assert_eq!(e4.file(), File::E);
+    assert_eq!(e4.rank(), Rank::FOURTH);
+    assert_eq!(e4, Square::new(File::E, Rank::FOURTH));
+    assert_eq!(e4.to_string(), "e4");
+
+    for square in Square::all() {
+        assert_eq!(square.to_string().parse::<Square>(), Ok(square));
+    }
+    assert_eq!(Square::all().next().unwrap().to_string(), "a1");
+    assert_eq!(Square::all().last().unwrap().to_string(), "h8");
+}
+
+#[test]
+fn test_invalid_square_names() {
+    for name in ["", "e", "e9", "i1", "E4", "4e", "e10", "é4"] {
+        assert!(name.parse::<Square>().is_err(), "{name} should not be a square");
+        assert_eq!(parse_position(name), Err(ChessError::InvalidSquare(name.to_string())));
+    }
+    assert!("h".parse::<File>().is_ok());
+    assert!("0".parse::<Rank>().is_err());
+}
+
+#[test]
+fn test_square_offsets_stay_on_the_board() {
+    let a1: Square = "a1".parse().unwrap();
+    assert_eq!(a1.offset(1, 2), parse_position("b3").ok());
+    assert_eq!(a1.offset(-1, 0), None);
+    assert_eq!(a1.offset(0, 8), None);
+    assert_eq!("h8".parse::<Square>().unwrap().offset(-7, -7), Some(a1));
+}
+
+#[test]
+fn test_recorded_moves_name_the_right_square() {
+    let mut board = setup_test_game();
+
+    play(&mut board, &[
+        ("a2","a3"), // White plays a3
+        ("g8","f6"), // Black plays Nf6
+    ]);
+
+    assert_eq!(board.position().piece_at(parse_position("a3").unwrap()), Some(Piece::Pawn(Player::White)));
+    let history: Vec<&str> = board.moves_history().iter().map(|m| m.trim()).collect();
+    assert_eq!(history[0], "a3");
+    assert!(history[1].ends_with("f6"));
+}
@@ -0,0 +1,64 @@
+mod common;
+
+use chessme::{ChessBoard, ChessError, Piece, Player, parse_position, parse_promotion};
+use common::{setup_test_game, play};
+
+
+fn try_move(board: &ChessBoard, start_str: &str, end_str: &str) -> Result<(), ChessError> {
+    let start = parse_position(start_str)?;
+    let end = parse_position(end_str)?;
+    board.check_move(start, end, None)
+}
+
+#[test]
+fn test_errors_name_the_problem() {
+    let board = setup_test_game();
+    let square = |name: &str| parse_position(name).unwrap();
+
+    assert_eq!(try_move(&board, "e4", "e5"), Err(ChessError::NoPieceOnSquare(square("e4"))));
+    assert_eq!(try_move(&board, "e7", "e5"), Err(ChessError::WrongSide { square: square("e7"), side_to_move: Player::White }));
+    assert_eq!(try_move(&board, "e2", "e5"), Err(ChessError::IllegalMove { from: square("e2"), to: square("e5") }));
+    assert_eq!(try_move(&board, "a1", "a4"), Err(ChessError::BlockedPath { from: square("a1"), to: square("a4") }));
+    assert_eq!(try_move(&board, "z9", "e4"), Err(ChessError::InvalidSquare("z9".to_string())));
+}
+
+#[test]
+fn test_moving_into_check_is_reported() {
+    let mut board = setup_test_game();
+    let square = |name: &str| parse_position(name).unwrap();
+
+    play(&mut board, &[
+        ("e2","e4"), // White plays e4
+        ("e7","e5"), // Black plays e5
+        ("g1","f3"), // White plays Nf3
+        ("b8","c6"), // Black plays Nc6
+        ("f1","b5"), // White plays Bb5
+        ("d7","d6"), // Black plays d6, pinning the knight
+        ("c2","c3"), // White plays c3
+    ]);
+
+    assert_eq!(try_move(&board, "c6", "d4"), Err(ChessError::LeavesKingInCheck { from: square("c6"), to: square("d4") }));
+}
+
+#[test]
+fn test_invalid_promotions_are_reported() {
+    let board = setup_test_game();
+    let start = parse_position("e2").unwrap();
+    let end = parse_position("e4").unwrap();
+
+    let king = Piece::King(Player::White);
+    assert_eq!(board.check_move(start, end, Some(king)), Err(ChessError::InvalidPromotion(king)));
+    let queen = Piece::Queen(Player::White);
+    assert_eq!(board.check_move(start, end, Some(queen)), Err(ChessError::InvalidPromotion(queen)));
+    assert_eq!(parse_promotion("k", Player::White), Err(ChessError::InvalidNotation("k".to_string())));
+}
+
+#[test]
+fn test_errors_are_std_errors() {
+    let error: Box<dyn std::error::Error> = Box::new(ChessError::NoPieceOnSquare(parse_position("e4").unwrap()));
+    assert_eq!(error.to_string(), "No piece on e4");
+
+    let mut board = setup_test_game();
+    let empty = parse_position("e4").unwrap();
+    assert_eq!(board.move_piece(empty, empty, None), Err(ChessError::NoPieceOnSquare(empty)));
+}
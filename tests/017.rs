@@ -0,0 +1,82 @@
+mod common;
+
+use chessme::{Bitboard, Piece, Player, Position, Square, parse_position};
+use common::{setup_test_game, play};
+
+
+// Every bitboard has to agree with the mailbox view.
+fn assert_consistent(position: &Position) {
+    let mut occupied = Bitboard::EMPTY;
+    for square in Square::all() {
+        match position.piece_at(square) {
+            Some(piece) => {
+                assert!(position.pieces(piece).contains(square), "{square} is missing from the {piece:?} bitboard");
+                assert!(position.occupied_by(piece.player()).contains(square));
+                occupied |= Bitboard::from_square(square);
+            },
+            None => assert!(!position.occupied().contains(square), "{square} should be empty"),
+        }
+    }
+    assert_eq!(position.occupied(), occupied);
+}
+
+#[test]
+fn test_initial_bitboards() {
+    let position = Position::new();
+
+    assert_eq!(position.occupied(), Bitboard(0xFFFF_0000_0000_FFFF));
+    assert_eq!(position.occupied_by(Player::White), Bitboard(0x0000_0000_0000_FFFF));
+    assert_eq!(position.pieces(Piece::Pawn(Player::Black)), Bitboard(0x00FF_0000_0000_0000));
+    assert_eq!(position.pieces(Piece::King(Player::White)).first(), parse_position("e1").ok());
+
+    let knights: Vec<String> = position.pieces(Piece::Knight(Player::White)).squares().map(|s| s.to_string()).collect();
+    assert_eq!(knights, vec!["b1", "g1"]);
+    assert_consistent(&position);
+}
+
+#[test]
+fn test_bitboards_follow_special_moves() {
+    let mut board = setup_test_game();
+
+    play(&mut board, &[
+        ("e2","e4"), // White plays e4
+        ("a7","a6"), // Black plays a6
+        ("e4","e5"), // White plays e5
+        ("d7","d5"), // Black plays d5
+        ("e5","d6"), // White plays exd6 en passant
+        ("g8","f6"), // Black plays Nf6
+        ("g1","f3"), // White plays Nf3
+        ("e7","d6"), // Black plays exd6
+        ("f1","c4"), // White plays Bc4
+        ("f8","e7"), // Black plays Be7
+        ("e1","g1"), // White castles king side
+    ]);
+    assert_consistent(board.position());
+    assert_eq!(board.position().pieces(Piece::Pawn(Player::White)).count(), 7);
+    assert_eq!(board.position().pieces(Piece::Pawn(Player::Black)).count(), 7);
+
+    // Undoing everything through unmake keeps them in step too.
+    while board.undo().is_some() {
+        assert_consistent(board.position());
+    }
+    assert_eq!(*board.position(), Position::new());
+}
+
+#[test]
+fn test_sliding_pieces_stop_at_blockers() {
+    let position = Position::from_pieces(&[
+        (parse_position("a1").unwrap(), Piece::Rook(Player::White)),
+        (parse_position("a5").unwrap(), Piece::Pawn(Player::Black)),
+        (parse_position("d1").unwrap(), Piece::King(Player::White)),
+        (parse_position("h8").unwrap(), Piece::King(Player::Black)),
+    ], Player::White);
+    let a1 = parse_position("a1").unwrap();
+
+    assert!(position.is_valid_rook_move(a1, parse_position("a5").unwrap()));
+    assert!(!position.is_valid_rook_move(a1, parse_position("a6").unwrap()));
+    assert!(position.is_valid_rook_move(a1, parse_position("c1").unwrap()));
+    assert!(!position.is_valid_rook_move(a1, parse_position("e1").unwrap()));
+
+    let rook_moves = position.legal_moves().iter().filter(|m| m.from == a1).count();
+    assert_eq!(rook_moves, 6);  // a2 to a5, b1 and c1
+}
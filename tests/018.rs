@@ -0,0 +1,68 @@
+mod common;
+
+use chessme::Position;
+use common::{setup_test_game, play};
+
+
+#[test]
+fn test_keys_match_polyglot() {
+    let mut board = setup_test_game();
+    assert_eq!(board.position().zobrist_key(), 0x463B96181691FC9C);
+
+    // Reference keys from the Polyglot book format specification.
+    let expected_keys: [(&str, &str, u64); 6] = [
+        ("e2", "e4", 0x823C9B50FD114196),
+        ("d7", "d5", 0x0756B94461C50FB0),
+        ("e4", "e5", 0x662FAFB965DB29D4),
+        ("f7", "f5", 0x22A48B5A8E47FF78),  // The en passant square counts, e5 can take on f6
+        ("e1", "e2", 0x652A607CA3F242C1),
+        ("e8", "f7", 0x00FDD303C946BDD9),
+    ];
+    for (start, end, key) in expected_keys {
+        play(&mut board, &[(start, end)]);
+        assert_eq!(board.position().zobrist_key(), key, "wrong key after {start} {end}");
+    }
+}
+
+#[test]
+fn test_keys_match_polyglot_with_en_passant_and_castling() {
+    let mut board = setup_test_game();
+
+    play(&mut board, &[
+        ("a2","a4"), // White plays a4
+        ("b7","b5"), // Black plays b5
+        ("h2","h4"), // White plays h4
+        ("b5","b4"), // Black plays b4
+        ("c2","c4"), // White plays c4
+    ]);
+    assert_eq!(board.position().zobrist_key(), 0x3C8123EA7B067637);
+
+    play(&mut board, &[
+        ("b4","c3"), // Black plays bxc3 en passant
+        ("a1","a3"), // White plays Ra3, losing the queen side castle
+    ]);
+    assert_eq!(board.position().zobrist_key(), 0x5C3F9B829B279560);
+}
+
+#[test]
+fn test_keys_follow_undo_and_transpositions() {
+    let mut board = setup_test_game();
+    let mut keys: Vec<u64> = vec![board.position().repetition_key()];
+
+    for (start, end) in [("g1","f3"), ("g8","f6"), ("b1","c3"), ("b8","c6")] {
+        play(&mut board, &[(start, end)]);
+        keys.push(board.position().repetition_key());
+    }
+    assert_eq!(board.position_history(), &keys[..]);
+
+    // The same position reached in another order has the same key.
+    let mut other = setup_test_game();
+    play(&mut other, &[("b1","c3"), ("b8","c6"), ("g1","f3"), ("g8","f6")]);
+    assert_eq!(other.position().zobrist_key(), board.position().zobrist_key());
+
+    while board.undo().is_some() {
+        keys.pop();
+        assert_eq!(board.position().zobrist_key(), *keys.last().unwrap());
+    }
+    assert_eq!(board.position().zobrist_key(), Position::new().zobrist_key());
+}
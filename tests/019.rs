@@ -0,0 +1,59 @@
+mod common;
+
+use chessme::{Piece, Player, Position, parse_position};
+use common::{setup_test_game, play};
+
+
+#[test]
+fn test_perft_initial_position() {
+    let position = Position::new();
+
+    assert_eq!(position.perft(0), 1);
+    assert_eq!(position.perft(1), 20);
+    assert_eq!(position.perft(2), 400);
+    assert_eq!(position.perft(3), 8902);
+    assert_eq!(position.perft(4), 197281);
+}
+
+#[test]
+fn test_divide_adds_up_to_perft() {
+    let position = Position::new();
+    let divide = position.divide(4);
+
+    assert_eq!(divide.len(), 20);
+    assert_eq!(divide.iter().map(|(_, nodes)| nodes).sum::<u64>(), 197281);
+
+    let e4 = divide.iter().find(|(mv, _)| mv.from == parse_position("e2").unwrap() && mv.to == parse_position("e4").unwrap());
+    assert_eq!(e4.map(|(_, nodes)| *nodes), Some(13160));
+    assert!(position.divide(0).is_empty());
+
+    // The same count from the board once e4 is played.
+    let mut board = setup_test_game();
+    play(&mut board, &[
+        ("e2","e4"), // White plays e4
+    ]);
+    assert_eq!(board.position().perft(3), 13160);
+}
+
+#[test]
+fn test_perft_with_en_passant_and_discovered_checks() {
+    // Position 3 of the usual perft suite: 8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - -
+    let square = |name: &str| parse_position(name).unwrap();
+    let position = Position::from_pieces(&[
+        (square("c7"), Piece::Pawn(Player::Black)),
+        (square("d6"), Piece::Pawn(Player::Black)),
+        (square("a5"), Piece::King(Player::White)),
+        (square("b5"), Piece::Pawn(Player::White)),
+        (square("h5"), Piece::Rook(Player::Black)),
+        (square("b4"), Piece::Rook(Player::White)),
+        (square("f4"), Piece::Pawn(Player::Black)),
+        (square("h4"), Piece::King(Player::Black)),
+        (square("e2"), Piece::Pawn(Player::White)),
+        (square("g2"), Piece::Pawn(Player::White)),
+    ], Player::White);
+
+    assert_eq!(position.perft(1), 14);
+    assert_eq!(position.perft(2), 191);
+    assert_eq!(position.perft(3), 2812);
+    assert_eq!(position.perft(4), 43238);
+}
@@ -0,0 +1,60 @@
+mod common;
+
+use chessme::{Piece, Player, Position, Square, parse_position};
+use common::{setup_test_game, play};
+
+
+fn names(squares: impl Iterator<Item = Square>) -> Vec<String> {
+    squares.map(|s| s.to_string()).collect()
+}
+
+#[test]
+fn test_squares_attacked_in_initial_position() {
+    let board = setup_test_game();
+    let square = |name: &str| parse_position(name).unwrap();
+
+    assert!(board.position().is_square_attacked(square("f3"), Player::White));
+    assert!(!board.position().is_square_attacked(square("f3"), Player::Black));
+    assert!(board.position().is_square_attacked(square("f6"), Player::Black));
+    assert!(!board.position().is_square_attacked(square("e4"), Player::White));
+
+    // f3 is covered by the e2 and g2 pawns and the g1 knight.
+    assert_eq!(names(board.position().attackers_of(square("f3")).squares()), vec!["g1", "e2", "g2"]);
+}
+
+#[test]
+fn test_attackers_of_both_sides() {
+    let mut board = setup_test_game();
+    let square = |name: &str| parse_position(name).unwrap();
+
+    play(&mut board, &[
+        ("e2","e4"), // White plays e4
+        ("d7","d5"), // Black plays d5
+        ("g1","f3"), // White plays Nf3
+        ("g8","f6"), // Black plays Nf6
+    ]);
+
+    let attackers = board.position().attackers_of(square("e4"));
+    assert_eq!(names(attackers.squares()), vec!["d5", "f6"]);
+    assert_eq!(names((attackers & board.position().occupied_by(Player::White)).squares()), Vec::<String>::new());
+
+    let attackers = board.position().attackers_of(square("d5"));
+    assert_eq!(names(attackers.squares()), vec!["e4", "f6", "d8"]);
+}
+
+#[test]
+fn test_sliders_are_stopped_by_blockers() {
+    let square = |name: &str| parse_position(name).unwrap();
+    let position = Position::from_pieces(&[
+        (square("a1"), Piece::Queen(Player::White)),
+        (square("d4"), Piece::Pawn(Player::Black)),
+        (square("e1"), Piece::King(Player::White)),
+        (square("h8"), Piece::King(Player::Black)),
+    ], Player::Black);
+
+    // The pawn shields h8 from the queen on the long diagonal, and stands attacked itself.
+    assert!(position.is_square_attacked(square("d4"), Player::White));
+    assert!(!position.is_square_attacked(square("h8"), Player::White));
+    assert!(position.is_square_attacked(square("a8"), Player::White));
+    assert!(!position.is_check());
+}
@@ -0,0 +1,85 @@
+mod common;
+
+use chessme::{Bitboard, Piece, Pin, Player, Position, Square, parse_position};
+use common::{setup_test_game, play};
+
+
+fn squares(names: &[&str]) -> Bitboard {
+    names.iter().fold(Bitboard::EMPTY, |set, name| set | Bitboard::from_square(parse_position(name).unwrap()))
+}
+
+#[test]
+fn test_checkers() {
+    let mut board = setup_test_game();
+    assert!(board.position().checkers().is_empty());
+
+    play(&mut board, &[
+        ("e2","e4"), // White plays e4
+        ("e7","e5"), // Black plays e5
+        ("f1","c4"), // White plays Bc4
+        ("b8","c6"), // Black plays Nc6
+        ("d1","h5"), // White plays Qh5
+        ("g8","f6"), // Black plays Nf6
+        ("h5","f7"), // White plays Qxf7#
+    ]);
+    assert_eq!(board.position().checkers(), squares(&["f7"]));
+}
+
+#[test]
+fn test_double_check() {
+    let square = |name: &str| parse_position(name).unwrap();
+    let position = Position::from_pieces(&[
+        (square("e8"), Piece::King(Player::Black)),
+        (square("e1"), Piece::Rook(Player::White)),
+        (square("d6"), Piece::Knight(Player::White)),
+        (square("a1"), Piece::King(Player::White)),
+    ], Player::Black);
+
+    assert_eq!(position.checkers(), squares(&["d6", "e1"]));
+    assert!(position.pinned(Player::Black).is_empty());
+}
+
+#[test]
+fn test_pinned_pieces() {
+    let mut board = setup_test_game();
+
+    play(&mut board, &[
+        ("e2","e4"), // White plays e4
+        ("e7","e5"), // Black plays e5
+        ("g1","f3"), // White plays Nf3
+        ("b8","c6"), // Black plays Nc6
+        ("f1","b5"), // White plays Bb5
+        ("d7","d6"), // Black plays d6, pinning the knight
+    ]);
+
+    let pins: Vec<Pin> = board.position().pinned(Player::Black);
+    assert_eq!(pins, vec![Pin {
+        square: parse_position("c6").unwrap(),
+        pinner: parse_position("b5").unwrap(),
+        ray: squares(&["d7", "b5"]),
+    }]);
+    assert!(board.position().pinned(Player::White).is_empty());
+}
+
+#[test]
+fn test_two_blockers_make_no_pin() {
+    let square = |name: &str| parse_position(name).unwrap();
+    let position = Position::from_pieces(&[
+        (square("e1"), Piece::King(Player::White)),
+        (square("e3"), Piece::Bishop(Player::White)),
+        (square("e5"), Piece::Pawn(Player::Black)),
+        (square("e8"), Piece::Queen(Player::Black)),
+        (square("a5"), Piece::Queen(Player::Black)),
+        (square("d2"), Piece::Knight(Player::White)),
+        (square("h8"), Piece::King(Player::Black)),
+    ], Player::White);
+
+    // The e-file has two pieces in the way, the a5-e1 diagonal only the knight.
+    let pinned: Vec<Square> = position.pinned(Player::White).iter().map(|pin| pin.square).collect();
+    assert_eq!(pinned, vec![square("d2")]);
+
+    // The knight itself is not part of the ray it may move along.
+    let pins = position.pinned(Player::White);
+    assert_eq!(pins[0].ray, squares(&["c3", "b4", "a5"]));
+    assert!(!pins[0].ray.contains(square("d2")));
+}
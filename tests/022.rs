@@ -0,0 +1,113 @@
+mod common;
+
+use chessme::{ChessError, Game, GameOutcome, Piece, Player, Position, parse_position};
+use common::{setup_test_session, play_squares};
+
+
+#[test]
+fn test_game_plays_and_records_moves() {
+    let mut game = setup_test_session();
+
+    play_squares(&mut game, &[
+        ("e2","e4"), // White plays e4
+        ("e7","e5"), // Black plays e5
+    ]);
+    assert_eq!(game.moves().len(), 2);
+    assert_eq!(*game.start_position(), Position::new());
+    assert_eq!(game.board().position().side_to_move(), Player::White);
+
+    // Illegal moves are refused and leave the game as it was.
+    let start = parse_position("e4").unwrap();
+    let end = parse_position("e6").unwrap();
+    assert!(game.play_squares(start, end, None).is_err());
+    assert_eq!(game.moves().len(), 2);
+
+    // A move generated by the board can be played as is.
+    let mv = game.board().position().legal_moves()[0];
+    assert!(game.play(mv).is_ok());
+    assert_eq!(game.moves().last(), Some(&mv));
+}
+
+#[test]
+fn test_game_outcome_and_pgn() {
+    let mut game = setup_test_session();
+
+    play_squares(&mut game, &[
+        ("f2","f3"), // White plays f3
+        ("e7","e5"), // Black plays e5
+        ("g2","g4"), // White plays g4
+        ("d8","h4"), // Black plays Qh4#
+    ]);
+    assert_eq!(game.outcome(), Some(GameOutcome::Checkmate { winner: Player::Black }));
+    assert_eq!(game.result(), "0-1");
+
+    let start = parse_position("a2").unwrap();
+    let end = parse_position("a3").unwrap();
+    assert_eq!(game.play_squares(start, end, None), Err(ChessError::GameOver(GameOutcome::Checkmate { winner: Player::Black })));
+
+    let pgn = game.to_pgn();
+    assert!(pgn.starts_with("[Event \"Chess Game\"]\n"));
+    assert!(pgn.contains("[White \"Player 1\"]\n[Black \"Player 2\"]\n[Result \"0-1\"]\n\n"));
+    assert!(pgn.ends_with("0-1"));
+}
+
+#[test]
+fn test_resignation_agreement_and_undo() {
+    let mut game = setup_test_session();
+
+    play_squares(&mut game, &[
+        ("e2","e4"), // White plays e4
+    ]);
+    game.resign(Player::Black);
+    assert_eq!(game.outcome(), Some(GameOutcome::Resignation { winner: Player::White }));
+    assert!(game.is_over());
+
+    // The first ending stands.
+    game.agree_draw();
+    assert_eq!(game.result(), "1-0");
+
+    // Taking back a move goes on with the game.
+    game.undo().unwrap();
+    assert!(!game.is_over());
+    assert_eq!(game.claim_draw(), None);
+
+    game.lose_on_time(Player::White);
+    assert_eq!(game.result(), "0-1");
+}
+
+#[test]
+fn test_tags() {
+    let mut game = setup_test_session();
+    assert_eq!(game.player_name(Player::White), "Player 1");
+    assert_eq!(game.tag("Round"), Some("1"));
+    assert_eq!(game.tag("SetUp"), None);
+
+    game.set_tag("Event", "Club \"Open\"");
+    game.set_tag("Annotator", "Me");
+    let names: Vec<&str> = game.tags().iter().map(|(name, _)| name.as_str()).collect();
+    assert_eq!(names, vec!["Event", "Site", "Date", "Round", "White", "Black", "Result", "Annotator"]);
+    assert!(game.to_pgn().contains("[Event \"Club \\\"Open\\\"\"]"));
+
+    // Dates follow the PGN format.
+    let date = game.tag("Date").unwrap();
+    assert_eq!(date.len(), 10);
+    assert_eq!(date.matches('.').count(), 2);
+}
+
+#[test]
+fn test_game_from_position() {
+    let square = |name: &str| parse_position(name).unwrap();
+    let position = Position::from_pieces(&[
+        (square("e1"), Piece::King(Player::White)),
+        (square("a7"), Piece::Pawn(Player::White)),
+        (square("e8"), Piece::King(Player::Black)),
+    ], Player::White);
+    let mut game = Game::from_position(position);
+
+    assert_eq!(game.tag("SetUp"), Some("1"));
+    assert!(game.tag("FEN").is_some());
+
+    assert!(game.play_squares(square("a7"), square("a8"), Some(Piece::Knight(Player::White))).is_ok());
+    assert_eq!(game.board().position().piece_at(square("a8")), Some(Piece::Knight(Player::White)));
+    assert_eq!(*game.start_position(), position);
+}
@@ -0,0 +1,98 @@
+use chessme::{CastlingRights, ChessBoard, ChessError, Piece, Player, Position, parse_position};
+
+
+const KIWIPETE: &str = "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1";
+
+fn invalid(reason: &str) -> Result<Position, ChessError> {
+    Err(ChessError::InvalidFen(reason.to_string()))
+}
+
+#[test]
+fn test_initial_position_from_fen() {
+    let position: Position = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1".parse().unwrap();
+    assert_eq!(position, Position::new());
+}
+
+#[test]
+fn test_all_fields_are_read() {
+    let board = ChessBoard::from_fen("rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR w Kq f6 0 3").unwrap();
+
+    assert_eq!(board.position().piece_at(parse_position("e5").unwrap()), Some(Piece::Pawn(Player::White)));
+    assert_eq!(board.position().side_to_move(), Player::White);
+    assert_eq!(board.position().castling_rights(), CastlingRights { white_king_side: true, white_queen_side: false, black_king_side: false, black_queen_side: true });
+    assert_eq!(board.position().en_passant(), parse_position("f6").ok());
+    assert_eq!((board.position().halfmove_clock(), board.position().fullmove_number()), (0, 3));
+
+    // The en passant capture is there to be played.
+    assert!(board.position().is_valid_move(parse_position("e5").unwrap(), parse_position("f6").unwrap()));
+}
+
+#[test]
+fn test_fen_round_trips_with_the_writer() {
+    for fen in [KIWIPETE, "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1", "4k3/8/8/8/8/8/8/4K3 b - - 0 1"] {
+        let position = Position::from_fen(fen).unwrap();
+        assert_eq!(position.write_to_fen(), fen);
+    }
+}
+
+#[test]
+fn test_keys_of_loaded_positions_match_polyglot() {
+    let keys = [
+        ("rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR w KQkq f6 0 3", 0x22A48B5A8E47FF78),
+        ("rnbqkbnr/p1pppppp/8/8/PpP4P/8/1P1PPPP1/RNBQKBNR b KQkq c3 0 3", 0x3C8123EA7B067637),
+        ("rnbqkbnr/p1pppppp/8/8/P6P/R1p5/1P1PPPP1/1NBQKBNR b Kkq - 1 4", 0x5C3F9B829B279560),
+    ];
+    for (fen, key) in keys {
+        assert_eq!(Position::from_fen(fen).unwrap().zobrist_key(), key, "{fen}");
+    }
+}
+
+#[test]
+fn test_errors_name_the_faulty_field() {
+    assert_eq!(Position::from_fen("8/8/8/8 w - - 0 1"), invalid("expected 8 ranks, found 4"));
+    assert_eq!(Position::from_fen("4k3/8/8/8/8/8/8/4K3 w -"), invalid("expected 6 fields, found 3"));
+    assert_eq!(Position::from_fen("4k3/8/8/8/8/8/8/4K3 b - -"), invalid("expected 6 fields, found 4"));
+    assert_eq!(Position::from_fen("4k3/8/8/8/8/8/8/4K3 w - - 0 1 extra"), invalid("expected 6 fields, found 7"));
+    assert_eq!(Position::from_fen("4k3/8/8/8/8/8/8/4K3 x - - 0 1"), invalid("invalid side to move 'x'"));
+    assert_eq!(Position::from_fen("4k3/8/8/8/8/8/8/4K2 w - - 0 1"), invalid("rank 1 has 7 squares instead of 8"));
+    assert_eq!(Position::from_fen("4k3/8/8/8/8/8/8/4K4 w - - 0 1"), invalid("rank 1 has 9 squares instead of 8"));
+    assert_eq!(Position::from_fen("4k3/8/8/44/8/8/8/4K3 w - - 0 1"), invalid("adjacent digits on rank 5"));
+    assert_eq!(Position::from_fen("4k3/8/8/8/8/8/8/3K13 w - - 0 1"), invalid("adjacent digits on rank 1"));
+    assert_eq!(Position::from_fen("4k3/8/8/8/8/8/8/4X3 w - - 0 1"), invalid("invalid piece 'X' on rank 1"));
+    assert_eq!(Position::from_fen("4k3/8/8/8/8/8/8/8 w - - 0 1"), invalid("White has 0 kings"));
+    assert_eq!(Position::from_fen("4k3/8/8/8/8/8/8/P3K3 w - - 0 1"), invalid("pawn on a1"));
+    assert_eq!(Position::from_fen("4k3/4R3/8/8/8/8/8/4K3 w - - 0 1"), invalid("Black is in check but not to move"));
+    assert_eq!(Position::from_fen("4k3/8/8/8/8/8/8/4K3 w KK - 0 1"), invalid("invalid castling rights 'KK'"));
+    assert_eq!(Position::from_fen("r3k2r/8/8/8/8/8/8/R3K2R w KkQq - 0 1"), invalid("invalid castling rights 'KkQq'"));
+    assert_eq!(Position::from_fen("4k3/8/8/8/8/8/8/4K3 w -- - 0 1"), invalid("invalid castling rights '--'"));
+    assert_eq!(Position::from_fen("4k3/8/8/8/8/8/8/4K3 w K - 0 1"), invalid("castling right 'K' without the king and rook in place"));
+    assert_eq!(Position::from_fen("4k3/8/8/8/8/8/8/4K3 w - e9 0 1"), invalid("invalid en passant square 'e9'"));
+    assert_eq!(Position::from_fen("4k3/8/8/8/8/8/8/4K3 w - e6 0 1"), invalid("no pawn can be taken en passant on e6"));
+    assert_eq!(Position::from_fen("4k3/4p3/8/4p3/8/8/8/4K3 w - e6 0 1"), invalid("no pawn can be taken en passant on e6"));
+    assert_eq!(Position::from_fen("4k3/8/8/8/8/8/8/4K3 w - - x 1"), invalid("invalid halfmove clock 'x'"));
+    assert_eq!(Position::from_fen("4k3/8/8/8/8/8/8/4K3 w - - +3 1"), invalid("invalid halfmove clock '+3'"));
+    assert_eq!(Position::from_fen("4k3/8/8/8/8/8/8/4K3 w - - 0 +1"), invalid("invalid fullmove number '+1'"));
+    assert_eq!(Position::from_fen("4k3/8/8/8/8/8/8/4K3 w - - 0 0"), invalid("invalid fullmove number '0'"));
+
+    let error = "4k3/8/8/8/8/8/8/4K3 x - - 0 1".parse::<Position>().unwrap_err();
+    assert_eq!(error.to_string(), "Invalid FEN: invalid side to move 'x'");
+}
+
+#[test]
+fn test_perft_from_fen() {
+    // Kiwipete, where castling, en passant and promotions all happen early.
+    let position = Position::from_fen(KIWIPETE).unwrap();
+    assert_eq!(position.perft(1), 48);
+    assert_eq!(position.perft(2), 2039);
+    assert_eq!(position.perft(3), 97862);
+
+    let position = Position::from_fen("r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1").unwrap();
+    assert_eq!(position.perft(1), 6);
+    assert_eq!(position.perft(2), 264);
+    assert_eq!(position.perft(3), 9467);
+
+    let position = Position::from_fen("rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8").unwrap();
+    assert_eq!(position.perft(1), 44);
+    assert_eq!(position.perft(2), 1486);
+    assert_eq!(position.perft(3), 62379);
+}
@@ -0,0 +1,83 @@
+mod common;
+
+use chessme::{EnPassantMode, Game, Position};
+use common::{setup_test_game, play};
+
+
+#[test]
+fn test_initial_fen() {
+    let board = setup_test_game();
+    assert_eq!(board.position().write_to_fen(), "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1");
+}
+
+#[test]
+fn test_fen_follows_the_game() {
+    let mut board = setup_test_game();
+
+    play(&mut board, &[
+        ("e2","e4"), // White plays e4
+        ("c7","c5"), // Black plays c5
+    ]);
+    assert_eq!(board.position().write_to_fen(), "rnbqkbnr/pp1ppppp/8/2p5/4P3/8/PPPP1PPP/RNBQKBNR w KQkq c6 0 2");
+
+    play(&mut board, &[
+        ("g1","f3"), // White plays Nf3
+    ]);
+    assert_eq!(board.position().write_to_fen(), "rnbqkbnr/pp1ppppp/8/2p5/4P3/5N2/PPPP1PPP/RNBQKB1R b KQkq - 1 2");
+
+    play(&mut board, &[
+        ("d8","a5"), // Black plays Qa5
+        ("f1","e2"), // White plays Be2
+        ("a5","a2"), // Black plays Qxa2
+        ("e1","g1"), // White castles king side
+    ]);
+    assert_eq!(board.position().write_to_fen(), "rnb1kbnr/pp1ppppp/8/2p5/4P3/5N2/qPPPBPPP/RNBQ1RK1 b kq - 1 4");
+}
+
+#[test]
+fn test_en_passant_only_when_capturable() {
+    let mut board = setup_test_game();
+
+    play(&mut board, &[
+        ("e2","e4"), // White plays e4
+    ]);
+    assert!(board.position().write_to_fen().contains(" b KQkq e3 0 1"));
+    assert!(board.position().write_to_fen_with(EnPassantMode::Capturable).contains(" b KQkq - 0 1"));
+
+    play(&mut board, &[
+        ("d7","d5"), // Black plays d5
+        ("e4","e5"), // White plays e5
+        ("f7","f5"), // Black plays f5, which e5 can take
+    ]);
+    assert!(board.position().write_to_fen_with(EnPassantMode::Capturable).contains(" w KQkq f6 0 3"));
+    assert_eq!(board.position().write_to_fen(), board.position().write_to_fen_with(EnPassantMode::Capturable));
+}
+
+#[test]
+fn test_en_passant_left_out_when_taking_is_illegal() {
+    // b5xc6 would open the fifth rank to the rook on h5.
+    let position = Position::from_fen("8/8/8/KPp4r/8/8/8/7k w - c6 0 2").unwrap();
+    assert_eq!(position.write_to_fen(), "8/8/8/KPp4r/8/8/8/7k w - c6 0 2");
+    assert_eq!(position.write_to_fen_with(EnPassantMode::Capturable), "8/8/8/KPp4r/8/8/8/7k w - - 0 2");
+    assert_eq!(position.position_key(), "8/8/8/KPp4r/8/8/8/7k w - -");
+}
+
+#[test]
+fn test_written_fen_reads_back() {
+    let mut board = setup_test_game();
+
+    play(&mut board, &[
+        ("d2","d4"), // White plays d4
+        ("g8","f6"), // Black plays Nf6
+        ("c2","c4"), // White plays c4
+        ("e7","e6"), // Black plays e6
+        ("b1","c3"), // White plays Nc3
+        ("f8","b4"), // Black plays Bb4
+    ]);
+    let fen = board.position().write_to_fen();
+    assert_eq!(Position::from_fen(&fen).unwrap(), *board.position());
+
+    // Games set up from a position record it in full.
+    let game = Game::from_position(*board.position());
+    assert_eq!(game.tag("FEN"), Some(fen.as_str()));
+}
@@ -0,0 +1,101 @@
+mod common;
+
+use chessme::{Game, Piece, Player, Position, parse_position};
+use common::{setup_test_game, play};
+
+
+fn san(position: &Position, start: &str, end: &str, promotion: Option<Piece>) -> String {
+    let mv = position.validate_move(parse_position(start).unwrap(), parse_position(end).unwrap(), promotion).unwrap();
+    position.to_san(mv)
+}
+
+#[test]
+fn test_history_is_in_san() {
+    let mut board = setup_test_game();
+
+    play(&mut board, &[
+        ("e2","e4"), // White plays e4
+        ("d7","d5"), // Black plays d5
+        ("e4","d5"), // White plays exd5
+        ("d8","d5"), // Black plays Qxd5
+        ("b1","c3"), // White plays Nc3
+        ("d5","e5"), // Black plays Qe5+
+        ("f1","e2"), // White plays Be2
+        ("b8","d7"), // Black plays Nd7
+        ("g1","f3"), // White plays Nf3
+        ("e5","f6"), // Black plays Qf6
+        ("e1","g1"), // White castles king side
+    ]);
+    let history: Vec<&str> = board.moves_history().iter().map(|m| m.as_str()).collect();
+    assert_eq!(history, vec!["e4", "d5", "exd5", "Qxd5", "Nc3", "Qe5+", "Be2", "Nd7", "Nf3", "Qf6", "O-O"]);
+}
+
+#[test]
+fn test_disambiguation() {
+    // Both knights can go to d7, only one to d5.
+    let position = Position::from_fen("1n2k3/8/5n2/8/8/8/8/4K3 b - - 0 1").unwrap();
+    assert_eq!(san(&position, "b8", "d7", None), "Nbd7");
+    assert_eq!(san(&position, "f6", "d7", None), "Nfd7");
+    assert_eq!(san(&position, "f6", "d5", None), "Nd5");
+
+    // Rooks on the same file are told apart by their rank.
+    let position = Position::from_fen("4k3/8/8/R7/8/8/8/R3K3 w - - 0 1").unwrap();
+    assert_eq!(san(&position, "a1", "a3", None), "R1a3");
+    assert_eq!(san(&position, "a5", "a3", None), "R5a3");
+    assert_eq!(san(&position, "a1", "b1", None), "Rb1");
+
+    // Three queens reaching e4, one of them sharing both file and rank with the others.
+    let position = Position::from_fen("2k5/8/8/8/1Q5Q/8/8/4K2Q w - - 0 1").unwrap();
+    assert_eq!(san(&position, "h4", "e4", None), "Qh4e4");
+    assert_eq!(san(&position, "h1", "e4", None), "Q1e4");
+    assert_eq!(san(&position, "b4", "e4", None), "Qbe4");
+}
+
+#[test]
+fn test_castling_and_promotion() {
+    let position = Position::from_fen("r2nk2r/4P3/8/8/8/8/8/R3K2R w KQkq - 0 1").unwrap();
+    assert_eq!(san(&position, "e1", "g1", None), "O-O");
+    assert_eq!(san(&position, "e1", "c1", None), "O-O-O");
+    assert_eq!(san(&position, "e7", "d8", None), "exd8=Q+");
+    assert_eq!(san(&position, "e7", "d8", Some(Piece::Knight(Player::White))), "exd8=N");
+}
+
+#[test]
+fn test_suffixes() {
+    let mut board = setup_test_game();
+
+    play(&mut board, &[
+        ("f2","f3"), // White plays f3
+        ("e7","e5"), // Black plays e5
+        ("g2","g4"), // White plays g4
+        ("d8","h4"), // Black plays Qh4#
+    ]);
+    assert_eq!(board.moves_history().back().map(|m| m.as_str()), Some("Qh4#"));
+
+    let position = Position::from_fen("8/4P3/8/8/8/8/k7/4K3 w - - 0 1").unwrap();
+    assert_eq!(san(&position, "e7", "e8", None), "e8=Q");
+    assert_eq!(san(&position, "e7", "e8", Some(Piece::Rook(Player::White))), "e8=R");
+
+    let position = Position::from_fen("2k5/4P3/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+    assert_eq!(san(&position, "e7", "e8", None), "e8=Q+");
+    let position = Position::from_fen("4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 1").unwrap();
+    assert_eq!(san(&position, "e5", "d6", None), "exd6");
+}
+
+#[test]
+fn test_pgn_numbering() {
+    let mut game = Game::new();
+
+    for (start, end) in [("e2","e4"), ("e7","e5"), ("g1","f3")] {
+        game.play_squares(parse_position(start).unwrap(), parse_position(end).unwrap(), None).unwrap();
+    }
+    assert!(game.to_pgn().ends_with("\n\n1. e4 e5 2. Nf3 *"));
+
+    // A game set up with Black to move starts with an ellipsis.
+    let position = Position::from_fen("4k3/8/8/8/8/8/8/R3K3 b Q - 0 12").unwrap();
+    let mut game = Game::from_position(position);
+    for (start, end) in [("e8","d7"), ("e1","c1")] {
+        game.play_squares(parse_position(start).unwrap(), parse_position(end).unwrap(), None).unwrap();
+    }
+    assert!(game.to_pgn().ends_with("\n\n12... Kd7 13. O-O-O+ *"));
+}
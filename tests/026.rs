@@ -0,0 +1,95 @@
+mod common;
+
+use chessme::{ChessError, GameOutcome, Piece, Player, Position, parse_position};
+use common::{setup_test_session, play_san};
+
+
+fn parsed(position: &Position, san: &str) -> (String, String, Option<Piece>) {
+    let mv = position.parse_san(san).unwrap();
+    (mv.from.to_string(), mv.to.to_string(), mv.promotion)
+}
+
+#[test]
+fn test_game_in_san() {
+    let mut game = setup_test_session();
+
+    play_san(&mut game, &["e4", "e5", "Nf3", "Nc6", "Bb5", "a6", "Bxc6", "dxc6", "O-O", "Bg4"]);
+    let history: Vec<&str> = game.board().moves_history().iter().map(|m| m.as_str()).collect();
+    assert_eq!(history, vec!["e4", "e5", "Nf3", "Nc6", "Bb5", "a6", "Bxc6", "dxc6", "O-O", "Bg4"]);
+    assert_eq!(game.board().position().piece_at(parse_position("g1").unwrap()), Some(Piece::King(Player::White)));
+}
+
+#[test]
+fn test_fools_mate_in_san() {
+    let mut game = setup_test_session();
+
+    play_san(&mut game, &["f3", "e5", "g4", "Qh4#"]);
+    assert_eq!(game.outcome(), Some(GameOutcome::Checkmate { winner: Player::Black }));
+    assert_eq!(game.play_san("a3"), Err(ChessError::GameOver(GameOutcome::Checkmate { winner: Player::Black })));
+}
+
+#[test]
+fn test_sloppy_notation() {
+    let position = Position::new();
+    assert_eq!(parsed(&position, "Ng1f3"), ("g1".to_string(), "f3".to_string(), None));
+    assert_eq!(parsed(&position, "Ng1-f3"), ("g1".to_string(), "f3".to_string(), None));
+    assert_eq!(parsed(&position, "e2e4"), ("e2".to_string(), "e4".to_string(), None));
+    assert_eq!(parsed(&position, "Nf3+"), ("g1".to_string(), "f3".to_string(), None));
+    assert_eq!(parsed(&position, "e4!?"), ("e2".to_string(), "e4".to_string(), None));
+
+    let position = Position::from_fen("rnbqkbnr/ppp1pppp/8/3p4/4P3/8/PPPP1PPP/RNBQKBNR w KQkq d6 0 2").unwrap();
+    assert_eq!(parsed(&position, "exd5"), ("e4".to_string(), "d5".to_string(), None));
+    assert_eq!(parsed(&position, "e4xd5"), ("e4".to_string(), "d5".to_string(), None));
+    assert_eq!(parsed(&position, "ed5"), ("e4".to_string(), "d5".to_string(), None));
+
+    let position = Position::from_fen("r3k3/8/8/8/8/8/8/4K2R w Kq - 0 1").unwrap();
+    assert_eq!(parsed(&position, "0-0"), ("e1".to_string(), "g1".to_string(), None));
+    let position = Position::from_fen("r3k3/8/8/8/8/8/8/4K2R b Kq - 0 1").unwrap();
+    assert_eq!(parsed(&position, "O-O-O"), ("e8".to_string(), "c8".to_string(), None));
+}
+
+#[test]
+fn test_promotions() {
+    let position = Position::from_fen("1n5k/P7/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+    let knight = Some(Piece::Knight(Player::White));
+    let queen = Some(Piece::Queen(Player::White));
+
+    assert_eq!(parsed(&position, "a8=N"), ("a7".to_string(), "a8".to_string(), knight));
+    assert_eq!(parsed(&position, "a8N"), ("a7".to_string(), "a8".to_string(), knight));
+    assert_eq!(parsed(&position, "axb8=q"), ("a7".to_string(), "b8".to_string(), queen));
+    assert_eq!(parsed(&position, "a8"), ("a7".to_string(), "a8".to_string(), queen));
+
+    assert_eq!(position.parse_san("a8=K"), Err(ChessError::InvalidPromotion(Piece::King(Player::White))));
+    assert_eq!(position.parse_san("Kd1=Q"), Err(ChessError::InvalidPromotion(Piece::Queen(Player::White))));
+
+    // A pawn capture always names the file it comes from.
+    let position = Position::from_fen("1r5k/P7/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+    assert_eq!(position.parse_san("b8=Q"), Err(ChessError::NoMatchingMove("b8=Q".to_string())));
+    assert_eq!(parsed(&position, "axb8=Q"), ("a7".to_string(), "b8".to_string(), queen));
+}
+
+#[test]
+fn test_ambiguity_and_errors() {
+    let position = Position::from_fen("1n2k3/8/5n2/8/8/8/8/4K3 b - - 0 1").unwrap();
+    assert_eq!(position.parse_san("Nd7"), Err(ChessError::AmbiguousMove("Nd7".to_string())));
+    assert_eq!(parsed(&position, "Nbd7"), ("b8".to_string(), "d7".to_string(), None));
+    assert_eq!(parsed(&position, "Nfd7"), ("f6".to_string(), "d7".to_string(), None));
+    assert_eq!(parsed(&position, "N6d7"), ("f6".to_string(), "d7".to_string(), None));
+
+    let position = Position::new();
+    assert_eq!(position.parse_san("Nf4"), Err(ChessError::NoMatchingMove("Nf4".to_string())));
+    assert_eq!(position.parse_san("O-O"), Err(ChessError::NoMatchingMove("O-O".to_string())));
+    assert_eq!(position.parse_san("e5"), Err(ChessError::NoMatchingMove("e5".to_string())));
+    assert_eq!(position.parse_san("Xe4"), Err(ChessError::InvalidNotation("Xe4".to_string())));
+    assert_eq!(position.parse_san("Ne9"), Err(ChessError::InvalidNotation("Ne9".to_string())));
+    assert_eq!(position.parse_san(""), Err(ChessError::InvalidNotation("".to_string())));
+
+    // A capture mark needs something to take, and a pawn capture the file it comes from.
+    assert_eq!(position.parse_san("xe4"), Err(ChessError::InvalidNotation("xe4".to_string())));
+    assert_eq!(position.parse_san("exe4"), Err(ChessError::NoMatchingMove("exe4".to_string())));
+    assert_eq!(position.parse_san("e2xe4"), Err(ChessError::NoMatchingMove("e2xe4".to_string())));
+    assert_eq!(position.parse_san("Nxf3"), Err(ChessError::NoMatchingMove("Nxf3".to_string())));
+
+    let error = Position::from_fen("1n2k3/8/5n2/8/8/8/8/4K3 b - - 0 1").unwrap().parse_san("Nd7").unwrap_err();
+    assert_eq!(error.to_string(), "'Nd7' could be more than one move, give the file or rank it starts from");
+}
@@ -0,0 +1,84 @@
+mod common;
+
+use chessme::{CastlingMode, ChessError, Piece, Player, Position, parse_position};
+use common::{setup_test_session, play_uci};
+
+
+#[test]
+fn test_game_in_uci() {
+    let mut game = setup_test_session();
+
+    play_uci(&mut game, &["e2e4", "e7e5", "g1f3", "b8c6", "f1c4", "g8f6", "e1g1"]);
+    let history: Vec<&str> = game.board().moves_history().iter().map(|m| m.as_str()).collect();
+    assert_eq!(history, vec!["e4", "e5", "Nf3", "Nc6", "Bc4", "Nf6", "O-O"]);
+
+    let uci: Vec<String> = game.moves().iter().map(|m| m.to_uci()).collect();
+    assert_eq!(uci, vec!["e2e4", "e7e5", "g1f3", "b8c6", "f1c4", "g8f6", "e1g1"]);
+}
+
+#[test]
+fn test_castling_both_ways() {
+    let position = Position::from_fen("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1").unwrap();
+
+    for (uci, king_side) in [("e1g1", true), ("e1h1", true), ("e1c1", false), ("e1a1", false)] {
+        let mv = position.parse_uci(uci).unwrap();
+        assert!(mv.is_castle(), "{uci}");
+        assert_eq!(mv.to.to_string(), if king_side { "g1" } else { "c1" });
+    }
+
+    let mv = position.parse_uci("e1h1").unwrap();
+    assert_eq!(mv.to_uci(), "e1g1");
+    assert_eq!(mv.to_uci_with(CastlingMode::Chess960), "e1h1");
+    let mv = position.parse_uci("e1c1").unwrap();
+    assert_eq!(mv.to_uci_with(CastlingMode::Chess960), "e1a1");
+
+    // Other moves read the same in both modes.
+    let mv = position.parse_uci("a1a8").unwrap();
+    assert_eq!(mv.to_uci_with(CastlingMode::Chess960), "a1a8");
+}
+
+#[test]
+fn test_promotions() {
+    let position = Position::from_fen("1n5k/P7/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+
+    let mv = position.parse_uci("a7a8n").unwrap();
+    assert_eq!(mv.promotion, Some(Piece::Knight(Player::White)));
+    assert_eq!(mv.to_uci(), "a7a8n");
+
+    let mv = position.parse_uci("a7b8q").unwrap();
+    assert_eq!(mv.promotion, Some(Piece::Queen(Player::White)));
+    assert!(mv.is_capture());
+    assert_eq!(mv.to_uci(), "a7b8q");
+
+    let under_promotions: Vec<String> = position.legal_moves().iter().filter(|m| m.to == parse_position("a8").unwrap()).map(|m| m.to_uci()).collect();
+    assert_eq!(under_promotions, vec!["a7a8q", "a7a8r", "a7a8b", "a7a8n"]);
+}
+
+#[test]
+fn test_errors() {
+    let position = Position::new();
+    let square = |name: &str| parse_position(name).unwrap();
+
+    assert_eq!(position.parse_uci("e2e5"), Err(ChessError::IllegalMove { from: square("e2"), to: square("e5") }));
+    assert_eq!(position.parse_uci("e3e4"), Err(ChessError::NoPieceOnSquare(square("e3"))));
+    assert_eq!(position.parse_uci("e2e4q"), Err(ChessError::InvalidPromotion(Piece::Queen(Player::White))));
+    assert_eq!(position.parse_uci("e2e4k"), Err(ChessError::InvalidNotation("e2e4k".to_string())));
+    assert_eq!(position.parse_uci("e2"), Err(ChessError::InvalidNotation("e2".to_string())));
+    assert_eq!(position.parse_uci("e2é4"), Err(ChessError::InvalidNotation("e2é4".to_string())));
+    assert_eq!(position.parse_uci("Nf3"), Err(ChessError::InvalidNotation("Nf3".to_string())));
+
+    // Castling through the pieces still in the way is refused either way.
+    assert!(position.parse_uci("e1g1").is_err());
+    assert!(position.parse_uci("e1h1").is_err());
+
+    // Only a king on e1 taking the rook in the corner is castling, other kings can't take their own rook.
+    let position = Position::from_fen("4k3/8/8/8/8/8/8/5K1R w - - 0 1").unwrap();
+    assert!(position.parse_uci("f1h1").is_err());
+    let position = Position::from_fen("4k3/8/8/8/8/8/8/RK6 w - - 0 1").unwrap();
+    assert!(position.parse_uci("b1a1").is_err());
+
+    // A promotion must name its piece.
+    let position = Position::from_fen("4k3/P7/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+    assert_eq!(position.parse_uci("a7a8"), Err(ChessError::InvalidNotation("a7a8".to_string())));
+    assert!(position.parse_uci("a7a8q").is_ok());
+}
@@ -0,0 +1,47 @@
+// Helpers shared by the integration tests, each test file uses only some of them.
+#![allow(dead_code)]
+
+use chessme::{ChessBoard, Game, parse_position};
+
+
+pub fn setup_test_game() -> ChessBoard {
+    ChessBoard::new()
+}
+
+// Plays the moves in order, each one checked to be legal first.
+pub fn play(board: &mut ChessBoard, positions: &[(&str, &str)]) {
+    for (start_str, end_str) in positions {
+        let start = parse_position(start_str).expect("Position can't be reached");
+        let end = parse_position(end_str).expect("Position can't be reached");
+        assert!(board.check_move(start, end, None).is_ok(), "{start_str} {end_str} should be valid");
+        board.move_piece(start, end, None).unwrap();
+    }
+}
+
+// A game session between two named players.
+pub fn setup_test_session() -> Game {
+    let mut game = Game::new();
+    game.set_players("Player 1", "Player 2");
+    game
+}
+
+// The same through a game session, which refuses illegal moves by itself.
+pub fn play_squares(game: &mut Game, positions: &[(&str, &str)]) {
+    for (start_str, end_str) in positions {
+        let start = parse_position(start_str).expect("Position can't be reached");
+        let end = parse_position(end_str).expect("Position can't be reached");
+        assert!(game.play_squares(start, end, None).is_ok(), "{start_str} {end_str} should be valid");
+    }
+}
+
+pub fn play_san(game: &mut Game, moves: &[&str]) {
+    for san in moves {
+        assert!(game.play_san(san).is_ok(), "{san} should be valid");
+    }
+}
+
+pub fn play_uci(game: &mut Game, moves: &[&str]) {
+    for uci in moves {
+        assert!(game.play_uci(uci).is_ok(), "{uci} should be valid");
+    }
+}